@@ -71,13 +71,13 @@ fn update_button_background(
         match *interaction {
             Interaction::Pressed => commands
                 .entity(entity)
-                .construct_scene(bsn! { BackgroundColor(LIME_600) }),
+                .reconstruct_scene(bsn! { BackgroundColor(LIME_600) }),
             Interaction::Hovered => commands
                 .entity(entity)
-                .construct_scene(bsn! { BackgroundColor(LIME_400) }),
+                .reconstruct_scene(bsn! { BackgroundColor(LIME_400) }),
             Interaction::None => commands
                 .entity(entity)
-                .construct_scene(bsn! { BackgroundColor(LIME_500) }),
+                .reconstruct_scene(bsn! { BackgroundColor(LIME_500) }),
         };
     }
 }
@@ -93,7 +93,7 @@ fn update_button_font(
             Interaction::Pressed => "fonts/Comic Sans.ttf",
             _ => "fonts/FiraSans-Bold.ttf",
         };
-        commands.entity(entity).construct_scene(bsn! {
+        commands.entity(entity).reconstruct_scene(bsn! {
             ConstructableTextFont {
                 font: @font,
             }
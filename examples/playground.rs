@@ -80,113 +80,98 @@ fn ui() -> impl Scene {
     }
 }
 
-#[derive(Component, Clone, Reflect)]
+#[derive(Component, Clone, Reflect, Construct)]
 #[reflect(Component)]
 struct HealthBar {
-    player_entity: ConstructEntity,
+    #[construct(entity, with = construct_health_bar)]
+    player_entity: Entity,
 }
 
-#[allow(missing_docs)]
-#[derive(Clone, Reflect)]
-pub struct HealthBarProps {
+/// Resolves the player entity of a [`HealthBar`] and spawns the bar for its current health.
+fn construct_health_bar(
+    context: &mut ConstructContext,
     player_entity: ConstructProp<ConstructEntity>,
-}
-
-impl Default for HealthBarProps {
-    fn default() -> Self {
-        Self {
-            player_entity: ConstructProp::Prop(Default::default()),
-        }
-    }
-}
-
-impl Construct for HealthBar {
-    type Props = HealthBarProps;
-    fn construct(
-        context: &mut ConstructContext,
-        props: Self::Props,
-    ) -> Result<Self, ConstructError> {
-        let player_entity = props.player_entity.construct(context)?;
-
-        let health = context
-            .world
-            .query::<&Health>()
-            .get(context.world, *player_entity)
-            .ok();
-
-        let text = health
-            .map(|h| format!("{}/{}", h.current, h.max))
-            .unwrap_or("-".to_string());
-
-        let normalized = health
-            .map(|h| (h.current as f32) / (h.max as f32))
-            .unwrap_or(0.0);
-
-        let bar_color = if normalized < 0.2 {
-            RED_500
-        } else if normalized < 0.8 {
-            YELLOW_500
-        } else {
-            LIME_500
-        };
-
-        let border_color = if normalized < 0.2 { RED_300 } else { GRAY_100 };
-
-        let width = 250.0f32;
-        let border = 3.0f32;
-        let border_radius = px(10.0f32);
-        let border_radius2 = px(7.0f32);
-        let bar_right = width - ((width - (border * 2.0)) * normalized);
-
-        let font_handle = context.construct::<ConstructHandle<Font>>("fonts/FiraSans-Bold.ttf")?;
-
-        let entity_patch = bsn! {
+) -> Result<Entity, ConstructError> {
+    let player_entity: Entity = player_entity.construct(context)?.into();
+
+    let health = context
+        .world
+        .query::<&Health>()
+        .get(context.world, player_entity)
+        .ok();
+
+    let text = health
+        .map(|h| format!("{}/{}", h.current, h.max))
+        .unwrap_or("-".to_string());
+
+    let normalized = health
+        .map(|h| (h.current as f32) / (h.max as f32))
+        .unwrap_or(0.0);
+
+    let bar_color = if normalized < 0.2 {
+        RED_500
+    } else if normalized < 0.8 {
+        YELLOW_500
+    } else {
+        LIME_500
+    };
+
+    let border_color = if normalized < 0.2 { RED_300 } else { GRAY_100 };
+
+    let width = 250.0f32;
+    let border = 3.0f32;
+    let border_radius = px(10.0f32);
+    let border_radius2 = px(7.0f32);
+    let bar_right = width - ((width - (border * 2.0)) * normalized);
+
+    let font_handle = context.construct::<ConstructHandle<Font>>("fonts/FiraSans-Bold.ttf")?;
+
+    let entity_patch = bsn! {
+        (
+            Node {
+                width: px(width),
+                height: px(50.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                border: px_all(border),
+            },
+            BackgroundColor(GRAY_100),
+            BorderColor(border_color),
+            BorderRadius {
+                bottom_left: border_radius,
+                bottom_right: border_radius,
+                top_left: border_radius,
+                top_right: border_radius,
+            },
+        ) [
             (
                 Node {
-                    width: px(width),
-                    height: px(50.0),
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::Center,
-                    border: px_all(border),
+                    position_type: PositionType::Absolute,
+                    top: Val::ZERO,
+                    bottom: px(border * 2.0),
+                    left: Val::ZERO,
+                    right: px(bar_right),
                 },
-                BackgroundColor(GRAY_100),
-                BorderColor(border_color),
+                BackgroundColor(bar_color),
                 BorderRadius {
-                    bottom_left: border_radius,
-                    bottom_right: border_radius,
-                    top_left: border_radius,
-                    top_right: border_radius,
+                    bottom_left: border_radius2,
+                    bottom_right: border_radius2,
+                    top_left: border_radius2,
+                    top_right: border_radius2,
                 },
-            ) [
-                (
-                    Node {
-                        position_type: PositionType::Absolute,
-                        top: Val::ZERO,
-                        bottom: px(border * 2.0),
-                        left: Val::ZERO,
-                        right: px(bar_right),
-                    },
-                    BackgroundColor(bar_color),
-                    BorderRadius {
-                        bottom_left: border_radius2,
-                        bottom_right: border_radius2,
-                        top_left: border_radius2,
-                        top_right: border_radius2,
-                    },
-                ),
-                (
-                    Text(text.clone()),
-                    TextFont {
-                        font: font_handle.clone(),
-                        font_size: 40.0,
-                    },
-                    TextColor(WHITE),
-                )
-            ]
-        };
-
-        context.construct_scene(entity_patch)?;
-
-        Ok(Self { player_entity })
-    }
+            ),
+            (
+                Text(text.clone()),
+                TextFont {
+                    font: font_handle.clone(),
+                    font_size: 40.0,
+                },
+                TextColor(WHITE),
+            )
+        ]
+    };
+
+    context.construct_scene(entity_patch)?;
+
+    Ok(player_entity)
 }
@@ -1,6 +1,7 @@
 //! Based on implementation from [bevy_asky](https://github.com/shanecelis/bevy_asky/blob/main/src/construct.rs).
 use alloc::borrow::Cow;
 use bevy::{ecs::system::EntityCommands, prelude::*, utils::all_tuples};
+use core::fmt;
 use thiserror::Error;
 
 /// Construction error
@@ -18,6 +19,165 @@ pub enum ConstructError {
         /// Message
         message: Cow<'static, str>,
     },
+    /// No entity with the given name
+    #[error("no entity named {name:?}")]
+    EntityNotFound {
+        /// Name of the entity
+        name: Cow<'static, str>,
+    },
+    /// Several entities with the given name
+    #[error("several entities named {name:?}")]
+    AmbiguousEntity {
+        /// Name of the entities
+        name: Cow<'static, str>,
+    },
+    /// Invalid asset path
+    #[error("invalid asset path {path:?}: {message}")]
+    AssetPathInvalid {
+        /// Asset path
+        path: Cow<'static, str>,
+        /// Message
+        message: Cow<'static, str>,
+    },
+    /// Error from a [`Construct`] implementation
+    #[error(transparent)]
+    Custom(Box<dyn core::error::Error + Send + Sync>),
+    /// Error with context about where it happened, appended as the construction of a scene unwinds
+    #[error("{error}, {context}")]
+    Context {
+        /// Where the error happened
+        context: ConstructErrorContext,
+        /// The error
+        error: Box<ConstructError>,
+    },
+}
+
+impl ConstructError {
+    /// Wraps the error with context about where it happened.
+    pub fn with_context(self, context: ConstructErrorContext) -> Self {
+        ConstructError::Context {
+            context,
+            error: Box::new(self),
+        }
+    }
+
+    /// The error without its context.
+    pub fn root_cause(&self) -> &ConstructError {
+        match self {
+            ConstructError::Context { error, .. } => error.root_cause(),
+            error => error,
+        }
+    }
+
+    /// The context of the error, from where it happened outwards.
+    pub fn context(&self) -> Vec<&ConstructErrorContext> {
+        let mut context = Vec::new();
+        let mut error = self;
+        while let ConstructError::Context {
+            context: outer,
+            error: inner,
+        } = error
+        {
+            context.push(outer);
+            error = inner;
+        }
+        context.reverse();
+        context
+    }
+}
+
+/// Where a [`ConstructError`] happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstructErrorContext {
+    /// While constructing the field of the props
+    Field(Cow<'static, str>),
+    /// While constructing the component with the given type path
+    Component(Cow<'static, str>),
+    /// While constructing the scene of the entity
+    Entity(Entity),
+    /// In the `bsn!` invocation at the given location
+    Invocation {
+        /// Source file
+        file: Cow<'static, str>,
+        /// Line
+        line: u32,
+        /// Column
+        column: u32,
+    },
+}
+
+impl fmt::Display for ConstructErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstructErrorContext::Field(field) => write!(f, "in field {field}"),
+            ConstructErrorContext::Component(component) => write!(f, "in component {component}"),
+            ConstructErrorContext::Entity(entity) => write!(f, "in entity {entity}"),
+            ConstructErrorContext::Invocation { file, line, column } => {
+                write!(f, "in bsn! at {file}:{line}:{column}")
+            }
+        }
+    }
+}
+
+/// How errors from construct and scene commands are handled. Panics by default.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SceneErrorPolicy {
+    /// Panic with the error.
+    #[default]
+    Panic,
+    /// Log the error and continue.
+    Log,
+    /// Send a [`SceneConstructFailed`] event and continue.
+    Event,
+}
+
+impl SceneErrorPolicy {
+    /// Handles a construction error of `entity` according to the policy.
+    pub fn handle(self, world: &mut World, entity: Entity, error: ConstructError) {
+        match self {
+            SceneErrorPolicy::Panic => panic!("failed to construct {entity}: {error}"),
+            SceneErrorPolicy::Log => error!("Failed to construct {entity}: {error}"),
+            SceneErrorPolicy::Event => {
+                match world.get_resource_mut::<Events<SceneConstructFailed>>() {
+                    Some(mut events) => {
+                        events.send(SceneConstructFailed { entity, error });
+                    }
+                    None => error!(
+                        "Failed to construct {entity}: {error}. SceneConstructFailed events are not registered."
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Sent when a construct or scene command fails with [`SceneErrorPolicy::Event`].
+#[derive(Event, Debug)]
+pub struct SceneConstructFailed {
+    /// Entity the command was constructing.
+    pub entity: Entity,
+    /// Error of the construction.
+    pub error: ConstructError,
+}
+
+/// Handles the error of a command with the [`SceneErrorPolicy`] resource.
+///
+/// `fallible` commands, the `try_` variants, log the error instead of panicking.
+pub(crate) fn handle_command_error(
+    world: &mut World,
+    entity: Entity,
+    error: ConstructError,
+    fallible: bool,
+) {
+    let policy = world
+        .get_resource::<SceneErrorPolicy>()
+        .copied()
+        .unwrap_or_default();
+    let policy = match policy {
+        SceneErrorPolicy::Panic if fallible => SceneErrorPolicy::Log,
+        policy => policy,
+    };
+    policy.handle(world, entity, error);
 }
 
 /// Construct property
@@ -41,6 +201,19 @@ impl<T: Construct> ConstructProp<T> {
     }
 }
 
+impl<T: Construct> ConstructProp<T> {
+    /// Returns the props to patch in place, replacing a value with the default props.
+    pub fn props_mut(&mut self) -> &mut T::Props {
+        if let ConstructProp::Value(_) = self {
+            *self = ConstructProp::Prop(T::Props::default());
+        }
+        match self {
+            ConstructProp::Prop(props) => props,
+            ConstructProp::Value(_) => unreachable!("values are replaced by props"),
+        }
+    }
+}
+
 impl<T: Construct> From<T> for ConstructProp<T> {
     fn from(value: T) -> Self {
         Self::Value(value)
@@ -98,22 +271,29 @@ where
 {
     fn apply(self, id: Entity, world: &mut World) {
         let mut context = ConstructContext { id, world };
-        let c = T::construct(&mut context, self.0).expect("component");
-        world.entity_mut(id).insert(c);
+        match T::construct(&mut context, self.0) {
+            Ok(c) => {
+                world.entity_mut(id).insert(c);
+            }
+            Err(error) => handle_command_error(world, id, error, false),
+        }
     }
 }
 
 /// Construct extension
 pub trait ConstructExt {
     /// Construct a type using the given properties and insert it onto the entity.
-    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands
+    fn construct<T: Construct + Bundle>(
+        &mut self,
+        props: impl Into<T::Props>,
+    ) -> EntityCommands<'_>
     where
         <T as Construct>::Props: Send;
 }
 
 impl<'w> ConstructExt for Commands<'w, '_> {
     // type Out = EntityCommands;
-    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands
+    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands<'_>
     where
         <T as Construct>::Props: Send,
     {
@@ -125,7 +305,7 @@ impl<'w> ConstructExt for Commands<'w, '_> {
 
 impl<'w> ConstructExt for ChildBuilder<'w> {
     // type Out = EntityCommands;
-    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands
+    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands<'_>
     where
         <T as Construct>::Props: Send,
     {
@@ -137,7 +317,7 @@ impl<'w> ConstructExt for ChildBuilder<'w> {
 
 impl<'w> ConstructExt for EntityCommands<'w> {
     // type Out = EntityCommands;
-    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands
+    fn construct<T: Construct + Bundle>(&mut self, props: impl Into<T::Props>) -> EntityCommands<'_>
     where
         <T as Construct>::Props: Send,
     {
@@ -179,3 +359,333 @@ all_tuples!(
     T,
     t
 );
+
+#[cfg(test)]
+mod test {
+    use core::{any::TypeId, marker::PhantomData};
+
+    use super::*;
+    use crate::{
+        bsn, register_derived_constructs, Construct, ConstructPatchExt, DynamicScene, EntityPatch,
+        EntityWorldMutSceneExt, ReflectConstruct, Scene,
+    };
+
+    /// Doubles its props when constructed.
+    #[derive(Reflect, Clone, PartialEq, Debug)]
+    struct Doubled(u32);
+
+    impl Construct for Doubled {
+        type Props = u32;
+
+        fn construct(_: &mut ConstructContext, props: u32) -> Result<Self, ConstructError> {
+            Ok(Doubled(props * 2))
+        }
+    }
+
+    #[derive(Reflect, Clone, PartialEq, Debug, Construct)]
+    enum Aim {
+        #[default]
+        Idle,
+        Point {
+            x: f32,
+            y: f32,
+        },
+        Follow(#[construct] Doubled),
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    struct Turret {
+        #[construct]
+        aim: Aim,
+    }
+
+    #[derive(Reflect, Clone, PartialEq, Debug, Construct)]
+    #[reflect(Construct)]
+    enum Slot<T: Send + Sync + 'static> {
+        #[default]
+        Empty,
+        Filled(u32),
+        _Typed(
+            #[construct(skip)]
+            #[reflect(ignore)]
+            PhantomData<T>,
+        ),
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    #[reflect(Component, Construct)]
+    struct Follow<T: Component> {
+        distance: f32,
+        #[construct(skip)]
+        #[reflect(ignore)]
+        _target: PhantomData<T>,
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    #[reflect(Component, Construct)]
+    struct Labelled<T: Clone + Default + Send + Sync + 'static> {
+        label: T,
+        #[construct]
+        count: Doubled,
+    }
+
+    fn double(_: &mut ConstructContext, speed: u32) -> Result<u32, ConstructError> {
+        Ok(speed * 2)
+    }
+
+    fn owner(context: &mut ConstructContext) -> Result<Entity, ConstructError> {
+        Ok(context.id)
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    struct Unit {
+        #[construct(default = 10)]
+        health: u32,
+        #[construct(skip, default = 3)]
+        armor: u32,
+        #[construct(with = double)]
+        speed: u32,
+        #[construct(skip, with = owner)]
+        owner: Entity,
+        #[construct(props = AimProps)]
+        aim: Aim,
+    }
+
+    mod local {
+        use bevy::reflect::Reflect;
+
+        /// Shares its name with Bevy's `Entity`, but isn't constructed through `ConstructEntity`.
+        #[derive(Reflect, Clone, Default, PartialEq, Debug)]
+        pub struct Entity(pub u32);
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    struct Follower {
+        #[construct(entity)]
+        leader: Entity,
+        #[construct(asset)]
+        icon: Handle<Font>,
+        tag: local::Entity,
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    #[construct(patch)]
+    struct Hero {
+        name: String,
+        #[construct(default = 1)]
+        level: u32,
+        #[construct]
+        score: Doubled,
+    }
+
+    #[derive(Reflect, Clone, Default, PartialEq, Debug)]
+    struct Caption {
+        text: String,
+        size: u32,
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    struct Banner {
+        #[construct]
+        #[construct(default = Caption { text: String::new(), size: 12 })]
+        caption: Caption,
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        register_derived_constructs(&mut app);
+        app
+    }
+
+    /// Constructs the scene on a new entity, and on another one through a [`DynamicScene`].
+    fn construct_both<S: Scene>(app: &mut App, scene: impl Fn() -> S) -> [Entity; 2] {
+        let world = app.world_mut();
+        let mut entity = world.spawn_empty();
+        entity.construct_scene(scene()).unwrap();
+        let static_id = entity.id();
+
+        let mut dynamic_scene = DynamicScene::default();
+        scene().dynamic_patch(&mut dynamic_scene);
+        let dynamic_id = world.spawn_empty().id();
+        dynamic_scene
+            .construct(&mut ConstructContext {
+                id: dynamic_id,
+                world,
+            })
+            .unwrap();
+
+        [static_id, dynamic_id]
+    }
+
+    /// Checks that the scene constructs `expected`, both statically and through a [`DynamicScene`].
+    fn assert_construct<C: Component + PartialEq + fmt::Debug, S: Scene>(
+        app: &mut App,
+        scene: impl Fn() -> S,
+        expected: C,
+    ) {
+        for id in construct_both(app, scene) {
+            assert_eq!(app.world().get(id), Some(&expected));
+        }
+    }
+
+    #[test]
+    fn test_derive_construct() {
+        let mut app = test_app();
+        let leader = app.world_mut().spawn(Name::new("leader")).id();
+        // Generic types are registered per parameter, `#[reflect(Construct)]` adds `ReflectConstruct`
+        app.register_type::<Follow<Turret>>()
+            .register_type::<Labelled<u32>>();
+
+        // Enums
+        assert_construct(&mut app, || bsn! { Turret }, Turret { aim: Aim::Idle });
+        assert_construct(
+            &mut app,
+            || bsn! { Turret { aim: @(AimProps::Follow(ConstructProp::Prop(3))) } },
+            Turret {
+                aim: Aim::Follow(Doubled(6)),
+            },
+        );
+        assert_construct(
+            &mut app,
+            || bsn! { Turret { aim: @(AimProps::Point { x: 1.0, y: 2.0 }) } },
+            Turret {
+                aim: Aim::Point { x: 1.0, y: 2.0 },
+            },
+        );
+
+        // Generics
+        assert_construct(
+            &mut app,
+            || bsn! { Follow::<Turret> { distance: 2.0 } },
+            Follow::<Turret> {
+                distance: 2.0,
+                _target: PhantomData,
+            },
+        );
+        assert_construct(
+            &mut app,
+            || bsn! { Labelled::<u32> { label: 5u32, count: @4u32 } },
+            Labelled {
+                label: 5u32,
+                count: Doubled(8),
+            },
+        );
+
+        // Field attributes
+        let ids = construct_both(&mut app, || {
+            bsn! { Unit { speed: 4u32, aim: AimProps::Follow(ConstructProp::Prop(1)) } }
+        });
+        for id in ids {
+            assert_eq!(
+                app.world().get(id),
+                Some(&Unit {
+                    health: 10,
+                    armor: 3,
+                    speed: 8,
+                    owner: id,
+                    aim: Aim::Follow(Doubled(2)),
+                })
+            );
+        }
+
+        // Wrapped fields
+        assert_construct(
+            &mut app,
+            || bsn! { Follower { leader: @"leader", tag: local::Entity(2) } },
+            Follower {
+                leader,
+                icon: Handle::default(),
+                tag: local::Entity(2),
+            },
+        );
+
+        // The unused parameter is carried by the default variant
+        let mut context = ConstructContext {
+            id: Entity::PLACEHOLDER,
+            world: app.world_mut(),
+        };
+        let slot = context.construct::<Slot<Turret>>(SlotProps::Filled(2));
+        assert_eq!(slot.unwrap(), Slot::Filled(2));
+        let slot = context.construct::<Slot<Turret>>(SlotProps::default());
+        assert_eq!(slot.unwrap(), Slot::Empty);
+    }
+
+    #[test]
+    fn test_derive_registration() {
+        let mut app = test_app();
+        {
+            let registry = app.world().resource::<AppTypeRegistry>().read();
+
+            let unit = registry.get(TypeId::of::<Unit>()).unwrap();
+            assert!(unit.data::<ReflectConstruct>().is_some());
+            assert!(unit.data::<ReflectComponent>().is_some());
+            assert!(registry.contains(TypeId::of::<UnitProps>()));
+
+            // Not a component
+            let aim = registry.get(TypeId::of::<Aim>()).unwrap();
+            assert!(aim.data::<ReflectConstruct>().is_some());
+            assert!(aim.data::<ReflectComponent>().is_none());
+
+            // Generic types need to be registered with their parameters
+            assert!(!registry.contains(TypeId::of::<Follow<Turret>>()));
+        }
+
+        app.register_type::<Follow<Turret>>();
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        let follow = registry.get(TypeId::of::<Follow<Turret>>()).unwrap();
+        assert!(follow.data::<ReflectConstruct>().is_some());
+    }
+
+    #[test]
+    fn test_derive_patches() {
+        let mut app = test_app();
+
+        assert_construct(
+            &mut app,
+            || EntityPatch {
+                inherit: (),
+                patch: (
+                    HeroPatch::name("fred"),
+                    HeroPatch::score(ConstructProp::Prop(5)),
+                ),
+                children: (),
+            },
+            Hero {
+                name: "fred".into(),
+                level: 1,
+                score: Doubled(10),
+            },
+        );
+
+        // Nested props keep the inner fields they don't set
+        let ids = construct_both(
+            &mut app,
+            || bsn! { Banner { caption: @Caption { text: "gold" } } },
+        );
+        for id in ids {
+            let caption = &app.world().get::<Banner>(id).unwrap().caption;
+            assert_eq!((caption.text.as_str(), caption.size), ("gold", 12));
+        }
+
+        // Values are replaced by the default props
+        let ids = construct_both(&mut app, || {
+            bsn! { (
+                Banner { caption: Caption { text: "silver".into(), size: 4 } },
+                Banner { caption: @Caption { text: "gold" } },
+            ) }
+        });
+        for id in ids {
+            let caption = &app.world().get::<Banner>(id).unwrap().caption;
+            assert_eq!((caption.text.as_str(), caption.size), ("gold", 0));
+        }
+
+        // Struct expressions with a base are props as a whole
+        let ids = construct_both(&mut app, || {
+            bsn! { Banner { caption: @Caption { size: 3, ..Default::default() } } }
+        });
+        for id in ids {
+            let caption = &app.world().get::<Banner>(id).unwrap().caption;
+            assert_eq!((caption.text.as_str(), caption.size), ("", 3));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use alloc::borrow::Cow;
 
 use bevy::{
+    asset::AssetPath,
     ecs::component::{ComponentHooks, StorageType},
     prelude::*,
     text::FontSmoothing,
@@ -24,6 +25,12 @@ impl<T: Asset> From<ConstructHandle<T>> for Handle<T> {
     }
 }
 
+impl<T: Asset> From<Handle<T>> for ConstructProp<ConstructHandle<T>> {
+    fn from(value: Handle<T>) -> Self {
+        ConstructProp::Value(value.into())
+    }
+}
+
 impl<T: Asset> Construct for ConstructHandle<T> {
     //type Props = AssetPath<'static>;
     type Props = &'static str;
@@ -32,11 +39,12 @@ impl<T: Asset> Construct for ConstructHandle<T> {
         context: &mut ConstructContext,
         path: Self::Props,
     ) -> Result<Self, ConstructError> {
-        // if let Err(err) = path.validate() {
-        //     return Err(ConstructError::InvalidProps {
-        //         message: format!("Invalid Asset Path: {err}").into(),
-        //     });
-        // }
+        if let Err(err) = AssetPath::try_parse(path) {
+            return Err(ConstructError::AssetPathInvalid {
+                path: path.into(),
+                message: err.to_string().into(),
+            });
+        }
         Ok(context.world.resource::<AssetServer>().load(path).into())
     }
 }
@@ -57,6 +65,12 @@ impl From<ConstructEntity> for Entity {
     }
 }
 
+impl From<Entity> for ConstructProp<ConstructEntity> {
+    fn from(value: Entity) -> Self {
+        ConstructProp::Value(value.into())
+    }
+}
+
 /// The construct prop for [`ConstructEntity`].
 #[derive(Default, Debug, Clone, Reflect)]
 pub enum EntityPath {
@@ -97,15 +111,17 @@ impl Construct for ConstructEntity {
         match props {
             EntityPath::Name(name) => {
                 let mut query = context.world.query::<(Entity, &Name)>();
-                let entity = query
+                let mut entities = query
                     .iter(context.world)
                     .filter(|(_, q_name)| q_name.as_str() == name)
-                    .map(|(entity, _)| ConstructEntity(entity))
-                    .next();
-
-                entity.ok_or_else(|| ConstructError::InvalidProps {
-                    message: format!("entity with name {} does not exist", name).into(),
-                })
+                    .map(|(entity, _)| ConstructEntity(entity));
+                let found = (entities.next(), entities.next());
+
+                match found {
+                    (Some(entity), None) => Ok(entity),
+                    (None, _) => Err(ConstructError::EntityNotFound { name }),
+                    (Some(_), Some(_)) => Err(ConstructError::AmbiguousEntity { name }),
+                }
             }
             EntityPath::Entity(entity) => Ok(ConstructEntity(entity)),
             _ => Err(ConstructError::InvalidProps {
@@ -116,54 +132,19 @@ impl Construct for ConstructEntity {
 }
 
 /// Constructable text font. Workaround for default-implmented [`TextFont`] in Bevy.
-#[derive(Clone, Debug, Reflect)]
+#[derive(Clone, Debug, Reflect, Construct)]
 #[reflect(Component, Construct)]
 pub struct ConstructableTextFont {
     /// Font
-    pub font: ConstructHandle<Font>,
+    #[construct(asset)]
+    pub font: Handle<Font>,
     /// Font size
+    #[construct(default = TextFont::default().font_size)]
     pub font_size: f32,
     /// Font smoothing
     pub font_smoothing: FontSmoothing,
 }
 
-#[allow(missing_docs)]
-#[derive(Clone, Reflect)]
-pub struct ConstructableTextFontProps {
-    pub font: ConstructProp<ConstructHandle<Font>>,
-    pub font_size: f32,
-    pub font_smoothing: FontSmoothing,
-}
-
-impl Default for ConstructableTextFontProps {
-    fn default() -> Self {
-        let TextFont {
-            font,
-            font_size,
-            font_smoothing,
-        } = TextFont::default();
-        Self {
-            font: ConstructProp::Value(font.into()),
-            font_size,
-            font_smoothing,
-        }
-    }
-}
-
-impl Construct for ConstructableTextFont {
-    type Props = ConstructableTextFontProps;
-    fn construct(
-        context: &mut ConstructContext,
-        props: Self::Props,
-    ) -> Result<Self, ConstructError> {
-        Ok(Self {
-            font: props.font.construct(context)?,
-            font_size: props.font_size,
-            font_smoothing: props.font_smoothing,
-        })
-    }
-}
-
 impl Component for ConstructableTextFont {
     const STORAGE_TYPE: StorageType = StorageType::Table;
 
@@ -171,7 +152,7 @@ impl Component for ConstructableTextFont {
         hooks.on_insert(|mut world, entity, _component_id| {
             let constructable = world.get::<ConstructableTextFont>(entity).unwrap().clone();
             world.commands().entity(entity).insert(TextFont {
-                font: constructable.font.into(),
+                font: constructable.font,
                 font_size: constructable.font_size,
                 font_smoothing: constructable.font_smoothing,
             });
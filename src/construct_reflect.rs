@@ -1,8 +1,11 @@
-use core::any::TypeId;
+use core::{any::TypeId, marker::PhantomData};
 
 use bevy::{
     app::App,
-    reflect::{FromType, PartialReflect, Reflect, Reflectable, TypePath},
+    ecs::{component::Component, reflect::AppTypeRegistry, reflect::ReflectComponent},
+    reflect::{
+        FromType, GetTypeRegistration, PartialReflect, Reflect, Reflectable, TypePath, TypeRegistry,
+    },
 };
 
 use crate::{Construct, ConstructContext, ConstructError};
@@ -60,6 +63,104 @@ where
     }
 }
 
+/// Registers a type deriving [`Construct`] in the type registry.
+///
+/// Submitted by `#[derive(Construct)]` for non-generic types, and collected by
+/// [`CantWaitForBsnPlugin`](crate::CantWaitForBsnPlugin), so the type, its props and [`ReflectConstruct`]
+/// are registered without any extra steps. Generic types are registered for each set of parameters with
+/// `register_type`, where `#[reflect(Construct)]` adds [`ReflectConstruct`]. Their props need to be registered
+/// as well for hot reloading.
+pub struct ConstructRegistration {
+    register: fn(&mut TypeRegistry),
+}
+
+impl ConstructRegistration {
+    /// Creates a registration calling `register` with the type registry of the app.
+    pub const fn new(register: fn(&mut TypeRegistry)) -> Self {
+        Self { register }
+    }
+}
+
+inventory::collect!(ConstructRegistration);
+
+/// Registers every [`ConstructRegistration`] submitted by `#[derive(Construct)]`.
+pub(crate) fn register_derived_constructs(app: &mut App) {
+    let registry = app.world().resource::<AppTypeRegistry>().clone();
+    let mut registry = registry.write();
+    for registration in inventory::iter::<ConstructRegistration> {
+        (registration.register)(&mut registry);
+    }
+}
+
+/// Probes which registrations apply to a type deriving [`Construct`].
+///
+/// `(&&RegistrationProbe::<T>::default()).register(registry)` resolves to the most specific of
+/// [`RegisterConstructComponent`], [`RegisterConstruct`] and [`RegisterNothing`] that `T` implements.
+#[doc(hidden)]
+pub struct RegistrationProbe<T>(PhantomData<T>);
+
+impl<T> Default for RegistrationProbe<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Registers a reflectable [`Construct`] component, along with its props.
+#[doc(hidden)]
+pub trait RegisterConstructComponent {
+    /// Registers the probed type.
+    fn register(&self, registry: &mut TypeRegistry);
+}
+
+impl<T> RegisterConstructComponent for &RegistrationProbe<T>
+where
+    T: Construct + Component + Reflectable,
+    T::Props: Reflect + TypePath + GetTypeRegistration,
+{
+    fn register(&self, registry: &mut TypeRegistry) {
+        register_construct::<T>(registry);
+        registry.register_type_data::<T, ReflectComponent>();
+    }
+}
+
+/// Registers a reflectable [`Construct`] type, along with its props.
+#[doc(hidden)]
+pub trait RegisterConstruct {
+    /// Registers the probed type.
+    fn register(&self, registry: &mut TypeRegistry);
+}
+
+impl<T> RegisterConstruct for &&RegistrationProbe<T>
+where
+    T: Construct + Reflectable,
+    T::Props: Reflect + TypePath + GetTypeRegistration,
+{
+    fn register(&self, registry: &mut TypeRegistry) {
+        register_construct::<T>(registry);
+    }
+}
+
+fn register_construct<T>(registry: &mut TypeRegistry)
+where
+    T: Construct + Reflectable,
+    T::Props: Reflect + TypePath + GetTypeRegistration,
+{
+    registry.register::<T>();
+    registry.register::<T::Props>();
+    registry.register_type_data::<T, ReflectConstruct>();
+}
+
+/// Fallback for types that aren't reflectable.
+#[doc(hidden)]
+pub trait RegisterNothing {
+    /// Registers the probed type.
+    fn register(&self, registry: &mut TypeRegistry);
+}
+
+impl<T> RegisterNothing for RegistrationProbe<T> {
+    fn register(&self, _registry: &mut TypeRegistry) {}
+}
+
 pub(crate) fn register_reflect_construct(app: &mut App) {
     use bevy::prelude::*;
 
@@ -1,10 +1,16 @@
 use core::any::TypeId;
 
 use bevy::{
-    log::{error, warn}, prelude::{AppTypeRegistry, BuildChildren, Component, Mut, ReflectComponent}, reflect::{PartialReflect, Reflect}, utils::{all_tuples, TypeIdMap}
+    log::warn,
+    prelude::{AppTypeRegistry, BuildChildren, Component, Mut, ReflectComponent},
+    reflect::{GetPath, PartialReflect, Reflect, ReflectMut, ReflectRef},
+    utils::{all_tuples, TypeIdMap},
 };
 
-use crate::{Construct, ConstructContext, ConstructError, ConstructPatch, ReflectConstruct};
+use crate::{
+    Conditional, Construct, ConstructContext, ConstructError, ConstructErrorContext,
+    ConstructPatch, Either, ReflectConstruct,
+};
 
 /// Dynamic patch
 pub trait DynamicPatch: Send + Sync + 'static {
@@ -34,6 +40,29 @@ all_tuples!(
     t
 );
 
+impl<P: DynamicPatch> DynamicPatch for Option<P> {
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        if let Some(patch) = self {
+            patch.dynamic_patch(scene);
+        }
+    }
+}
+
+impl<L: DynamicPatch, R: DynamicPatch> DynamicPatch for Either<L, R> {
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        match self {
+            Either::Left(patch) => patch.dynamic_patch(scene),
+            Either::Right(patch) => patch.dynamic_patch(scene),
+        }
+    }
+}
+
+impl<P: DynamicPatch> DynamicPatch for Conditional<P> {
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        self.value.dynamic_patch(scene);
+    }
+}
+
 impl<C, F, P> DynamicPatch for ConstructPatch<C, F>
 where
     C: Construct<Props = P> + Component + PartialReflect + Sync + Send + 'static,
@@ -41,12 +70,24 @@ where
     F: Fn(&mut C::Props) + Clone + Sync + Send + 'static,
 {
     fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        #[cfg(feature = "hot_reload")]
+        scene.provenance.record(
+            TypeId::of::<C>(),
+            core::any::type_name::<C>(),
+            self.fields.map(|fields| fields.iter().copied()),
+        );
+
         let patches = scene.component_props.entry(TypeId::of::<C>()).or_default();
 
         let func = self.func.clone();
+        let replace = self.replace;
 
         patches.push(Box::new(move |props: &mut dyn Reflect| {
-            (func)(props.downcast_mut::<C::Props>().unwrap());
+            let props = props.downcast_mut::<C::Props>().unwrap();
+            if replace {
+                *props = Default::default();
+            }
+            (func)(props);
         }));
     }
 }
@@ -75,16 +116,29 @@ pub struct DynamicScene {
     pub component_props: TypeIdMap<Vec<Box<dyn ReflectPatch>>>,
     /// Children of the scene.
     pub children: Vec<DynamicScene>,
+    /// Maps component type ids to the paths of the fields appended to by the patches.
+    ///
+    /// When patching an existing component, these fields are rebuilt from the props of the scene,
+    /// so constructing the scene again doesn't append the items twice.
+    pub(crate) appended_fields: TypeIdMap<Vec<String>>,
+    #[cfg(feature = "hot_reload")]
+    pub(crate) provenance: crate::hot_reload::ProvenanceRecorder,
 }
 
 impl DynamicScene {
     /// Constructs the dynamic patches in the scene, inserts the resulting components, and spawns children recursively.
     pub fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        let id = context.id;
+        self.construct_entity(context)
+            .map_err(|error| error.with_context(ConstructErrorContext::Entity(id)))
+    }
+
+    fn construct_entity(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
         // Construct components
         for (type_id, patches) in self.component_props {
             context
                 .world
-                .resource_scope(|world, app_registry: Mut<AppTypeRegistry>| {
+                .resource_scope(|world, app_registry: Mut<AppTypeRegistry>| -> Result<(), ConstructError> {
                     let registry = app_registry.read();
                     let t = registry
                         .get(type_id)
@@ -94,14 +148,14 @@ impl DynamicScene {
                             "No registered ReflectConstruct for component: {:?}. Skipping construction. Consider adding #[reflect(Construct)].",
                             t.type_info().type_path()
                         );
-                        return;
+                        return Ok(());
                     };
                     let Some(reflect_component) = t.data::<ReflectComponent>() else {
                         warn!(
                             "No registered ReflectComponent for component: {:?}. Skipping construction. Consider adding #[reflect(Component)].",
                             t.type_info().type_path()
                         );
-                        return;
+                        return Ok(());
                     };
 
                     if reflect_construct.props_type_id == type_id {
@@ -113,12 +167,29 @@ impl DynamicScene {
 
                         let entity = world.entity_mut(context.id);
                         let mut component = reflect_component.reflect_mut(entity).expect("component should exist");
-                        
+
                         for patch in patches.iter() {
                             patch.patch(component.as_reflect_mut());
                         }
 
-                        return;
+                        if let Some(appended_fields) = self.appended_fields.get(&type_id) {
+                            let mut props = reflect_construct.default_props();
+                            for patch in patches.iter() {
+                                patch.patch(props.as_mut());
+                            }
+                            for path in appended_fields {
+                                let Ok(field) = component.reflect_path_mut(path.as_str()) else { continue };
+                                let Ok(rebuilt) = props.reflect_path(path.as_str()) else { continue };
+                                if let (ReflectMut::List(list), ReflectRef::List(items)) = (field.reflect_mut(), rebuilt.reflect_ref()) {
+                                    while list.pop().is_some() {}
+                                    for item in items.iter() {
+                                        list.push(item.clone_value());
+                                    }
+                                }
+                            }
+                        }
+
+                        return Ok(());
                     }
 
                     // Prepare props
@@ -128,27 +199,25 @@ impl DynamicScene {
                     }
 
                     // Construct component
-                    let Ok(component) = reflect_construct.construct(
+                    let component = reflect_construct.construct(
                         &mut ConstructContext {
                             id: context.id,
                             world,
                         },
                         props,
-                    ) else {
-                        error!(
-                            "failed to construct component: {:?}.",
-                            t.type_info().type_path()
-                        );
-                        return;
-                    };
+                    ).map_err(|error| error.with_context(ConstructErrorContext::Component(t.type_info().type_path().into())))?;
 
                     // Insert component on entity
                     // TODO: Partial/hot patch for non Default + Clone constructs?
                     let mut entity = world.entity_mut(context.id);
                     reflect_component.apply_or_insert(&mut entity, component.as_ref(), &registry);
-                });
+                    Ok(())
+                })?;
         }
 
+        #[cfg(feature = "hot_reload")]
+        self.provenance.apply(context.world, context.id);
+
         // Spawn children
         for child in self.children {
             let child_id = context.world.spawn_empty().id();
@@ -166,4 +235,74 @@ impl DynamicScene {
     pub fn push_child(&mut self, child: DynamicScene) {
         self.children.push(child);
     }
+
+    /// Creates an empty scene to be pushed as a child of this scene, continuing its provenance tracking.
+    pub fn new_child(&self) -> DynamicScene {
+        DynamicScene {
+            #[cfg(feature = "hot_reload")]
+            provenance: self.provenance.child(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{Children, Reflect, World};
+
+    use super::*;
+    use crate::{bsn, ConstructPatchExt, Scene};
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Construct)]
+    struct Score(u32);
+
+    #[derive(Component, Reflect, Clone)]
+    #[reflect(Component, Construct)]
+    struct Broken;
+
+    impl Construct for Broken {
+        type Props = ();
+
+        fn construct(_: &mut ConstructContext, _: ()) -> Result<Self, ConstructError> {
+            Err(ConstructError::InvalidProps {
+                message: "broken".into(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_failed_component_error() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let mut registry = world.resource::<AppTypeRegistry>().write();
+            registry.register::<Score>();
+            registry.register::<Broken>();
+        }
+
+        let mut scene = DynamicScene::default();
+        bsn! { Score(1u32) [ (Score(2u32), Broken) ] }.dynamic_patch(&mut scene);
+        let id = world.spawn_empty().id();
+        let error = scene
+            .construct(&mut ConstructContext {
+                id,
+                world: &mut world,
+            })
+            .unwrap_err();
+
+        // The error reaches the caller, with the component and entities it happened in
+        assert!(matches!(
+            error.root_cause(),
+            ConstructError::InvalidProps { .. }
+        ));
+        let context = error.context();
+        assert!(matches!(
+            context[0],
+            ConstructErrorContext::Component(component) if component.ends_with("Broken")
+        ));
+        let child = world.get::<Children>(id).unwrap()[0];
+        assert!(matches!(context[1], ConstructErrorContext::Entity(entity) if *entity == child));
+        assert!(matches!(context[2], ConstructErrorContext::Entity(entity) if *entity == id));
+    }
 }
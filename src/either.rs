@@ -0,0 +1,29 @@
+//! Choices between scenes or patches, used for conditional `bsn!` entries.
+
+/// Either of two scenes or patches.
+///
+/// `if`/`else` and `match` entries in `bsn!` wrap each branch in [`Either`], nested for more than two branches,
+/// so the branches can have different types.
+///
+/// Conditional patches only implement [`DynamicPatch`](crate::DynamicPatch) and [`HotPatch`](crate::HotPatch),
+/// which apply the active branch alone. A [`Patch`](crate::Patch) constructs a fixed bundle, which can't leave out
+/// the branches that weren't taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Either<L, R> {
+    /// The first branch.
+    Left(L),
+    /// The second branch.
+    Right(R),
+}
+
+/// A conditional `bsn!` entry, with the index of the branch that was taken.
+///
+/// Hot-reloadable scenes record the index, so the active branch can be hot reloaded when its condition can't be
+/// evaluated from the source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Conditional<T> {
+    /// Index of the active branch. One past the last branch of an `if` without `else` when no branch was taken.
+    pub branch: usize,
+    /// The scene or patch of the active branch.
+    pub value: T,
+}
@@ -1,12 +1,19 @@
 use bevy::{
+    ecs::{component::ComponentId, reflect::ReflectComponent},
     prelude::{
-        BuildChildren, ChildBuild, ChildBuilder, Commands, Entity, EntityCommand, EntityCommands,
-        World,
+        AppTypeRegistry, BuildChildren, ChildBuild, ChildBuilder, Children, Commands,
+        DespawnRecursiveExt, Entity, EntityCommand, EntityCommands, EntityWorldMut, OnAdd, Parent,
+        Trigger, World,
     },
+    reflect::PartialReflect,
     utils::all_tuples,
 };
 
-use crate::{ConstructContext, ConstructError, DynamicPatch, DynamicScene, HotPatch, Patch};
+use crate::{
+    handle_command_error, record_scene_instance, record_spawned_children, refresh_scene_instance,
+    Conditional, ConstructContext, ConstructError, ConstructErrorContext, DynamicPatch,
+    DynamicScene, Either, HotPatch, ReconcileChildren, ReconstructSpawned, SceneKey,
+};
 
 /// Convenience trait for [`EntityPatch`].
 pub trait Scene: Sized {
@@ -21,6 +28,166 @@ pub trait Scene: Sized {
 
     /// Dynamically patches the scene and pushes it as a child of the [`DynamicScene`].
     fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene);
+
+    /// Whether this scene is a [`Fragment`], which has no entity of its own and splices its children into the parent.
+    const IS_FRAGMENT: bool = false;
+
+    /// Whether this scene value is a [`Fragment`]. Defaults to [`Scene::IS_FRAGMENT`].
+    ///
+    /// Boxed scenes only know it at runtime.
+    fn is_fragment(&self) -> bool {
+        Self::IS_FRAGMENT
+    }
+
+    /// Constructs the scene again on an entity it was constructed on before, reusing the existing children.
+    ///
+    /// Defaults to [`Scene::construct`].
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.construct(context)
+    }
+
+    /// Reconstructs the scene as a child of the context entity, reusing one of the existing `children` if it matches.
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        children.reconstruct_child(self, context)
+    }
+
+    /// The key matching the scene to an existing child when reconstructing, instead of its position.
+    fn key(&self) -> Option<&SceneKey> {
+        None
+    }
+
+    /// Boxes the scene, so scenes of different types can be returned or stored together.
+    fn boxed(self) -> Box<dyn DynScene + Send>
+    where
+        Self: Send + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+/// Dyn-compatible version of [`Scene`], implemented for every [`Scene`].
+///
+/// `Box<dyn DynScene + Send>` implements [`Scene`] itself.
+pub trait DynScene {
+    /// Boxed version of [`Scene::construct`].
+    fn construct_boxed(
+        self: Box<Self>,
+        context: &mut ConstructContext,
+    ) -> Result<(), ConstructError>;
+
+    /// Boxed version of [`Scene::spawn`].
+    fn spawn_boxed(self: Box<Self>, context: &mut ConstructContext) -> Result<(), ConstructError>;
+
+    /// Same as [`Scene::dynamic_patch`].
+    fn dynamic_patch_dyn(&mut self, scene: &mut DynamicScene);
+
+    /// Same as [`Scene::dynamic_patch_as_child`].
+    fn dynamic_patch_as_child_dyn(&mut self, scene: &mut DynamicScene);
+
+    /// Boxed version of [`Scene::reconstruct`].
+    fn reconstruct_boxed(
+        self: Box<Self>,
+        context: &mut ConstructContext,
+    ) -> Result<(), ConstructError>;
+
+    /// Boxed version of [`Scene::reconstruct_child`].
+    fn reconstruct_child_boxed(
+        self: Box<Self>,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError>;
+
+    /// Same as [`Scene::key`].
+    fn key_dyn(&self) -> Option<&SceneKey>;
+
+    /// Same as [`Scene::is_fragment`].
+    fn is_fragment_dyn(&self) -> bool;
+}
+
+impl<S: Scene> DynScene for S {
+    fn construct_boxed(
+        self: Box<Self>,
+        context: &mut ConstructContext,
+    ) -> Result<(), ConstructError> {
+        (*self).construct(context)
+    }
+
+    fn spawn_boxed(self: Box<Self>, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        (*self).spawn(context)
+    }
+
+    fn dynamic_patch_dyn(&mut self, scene: &mut DynamicScene) {
+        self.dynamic_patch(scene);
+    }
+
+    fn dynamic_patch_as_child_dyn(&mut self, scene: &mut DynamicScene) {
+        self.dynamic_patch_as_child(scene);
+    }
+
+    fn reconstruct_boxed(
+        self: Box<Self>,
+        context: &mut ConstructContext,
+    ) -> Result<(), ConstructError> {
+        (*self).reconstruct(context)
+    }
+
+    fn reconstruct_child_boxed(
+        self: Box<Self>,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        (*self).reconstruct_child(context, children)
+    }
+
+    fn key_dyn(&self) -> Option<&SceneKey> {
+        self.key()
+    }
+
+    fn is_fragment_dyn(&self) -> bool {
+        self.is_fragment()
+    }
+}
+
+impl Scene for Box<dyn DynScene + Send> {
+    fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.construct_boxed(context)
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.spawn_boxed(context)
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        (**self).dynamic_patch_dyn(scene);
+    }
+
+    fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
+        (**self).dynamic_patch_as_child_dyn(scene);
+    }
+
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.reconstruct_boxed(context)
+    }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        self.reconstruct_child_boxed(context, children)
+    }
+
+    fn key(&self) -> Option<&SceneKey> {
+        (**self).key_dyn()
+    }
+
+    fn is_fragment(&self) -> bool {
+        (**self).is_fragment_dyn()
+    }
 }
 
 /// Zero or more [`Scene`]es forming a set of children or inherited patches. Implemented for tuples of [`Scene`].
@@ -36,6 +203,13 @@ pub trait SceneTuple {
 
     /// Pushes the scenes in the tuple as children of the dynamic scene.
     fn push_dynamic_children(&mut self, scene: &mut DynamicScene);
+
+    /// Reconstructs the scenes in the tuple as children of the context entity, by calling [`Scene::reconstruct_child`].
+    fn reconstruct_children(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError>;
 }
 
 impl SceneTuple for () {
@@ -48,6 +222,14 @@ impl SceneTuple for () {
     fn dynamic_patch(&mut self, _: &mut DynamicScene) {}
 
     fn push_dynamic_children(&mut self, _: &mut DynamicScene) {}
+
+    fn reconstruct_children(
+        self,
+        _: &mut ConstructContext,
+        _: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        Ok(())
+    }
 }
 
 // Tuple impls
@@ -76,6 +258,16 @@ macro_rules! impl_scene_tuple {
                 let ($($s,)*) = self;
                 $($s.dynamic_patch_as_child(scene);)*
             }
+
+            fn reconstruct_children(
+                self,
+                context: &mut ConstructContext,
+                children: &mut ReconcileChildren,
+            ) -> Result<(), ConstructError> {
+                let ($($s,)*) = self;
+                $($s.reconstruct_child(context, children)?;)*
+                Ok(())
+            }
         }
     };
 }
@@ -93,7 +285,7 @@ all_tuples!(
 pub struct EntityPatch<I, P, C>
 where
     I: SceneTuple,
-    P: Patch + DynamicPatch + HotPatch,
+    P: DynamicPatch + HotPatch,
     C: SceneTuple,
 {
     /// Inherited scenes.
@@ -107,7 +299,7 @@ where
 impl<I, P, C> Scene for EntityPatch<I, P, C>
 where
     I: SceneTuple,
-    P: Patch + DynamicPatch + HotPatch,
+    P: DynamicPatch + HotPatch,
     C: SceneTuple,
 {
     /// Constructs an [`EntityPatch`], inserts the resulting bundle to the context entity, and recursively spawns children.
@@ -115,12 +307,17 @@ where
         if !I::IS_EMPTY {
             // Dynamic scene
             let mut dynamic_scene = DynamicScene::default();
+            #[cfg(feature = "hot_reload")]
+            dynamic_scene.provenance.inherit_sources(context.world);
             self.dynamic_patch(&mut dynamic_scene);
             dynamic_scene.construct(context)?;
         } else {
             // Static scene
-            self.patch.hot_patch(context)?;
-            self.children.spawn_children(context)?;
+            let id = context.id;
+            self.patch
+                .hot_patch(context)
+                .and_then(|()| self.children.spawn_children(context))
+                .map_err(|error| error.with_context(ConstructErrorContext::Entity(id)))?;
         }
 
         Ok(())
@@ -140,7 +337,11 @@ where
 
     fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
         // Apply the inherited patches
+        #[cfg(feature = "hot_reload")]
+        scene.provenance.enter_inherit();
         self.inherit.dynamic_patch(scene);
+        #[cfg(feature = "hot_reload")]
+        scene.provenance.exit_inherit();
 
         // Apply this patch itself
         self.patch.dynamic_patch(scene);
@@ -151,10 +352,201 @@ where
 
     /// Dynamically patches the scene and pushes it as a child of the [`DynamicScene`].
     fn dynamic_patch_as_child(&mut self, parent_scene: &mut DynamicScene) {
-        let mut child_scene = DynamicScene::default();
+        let mut child_scene = parent_scene.new_child();
         self.dynamic_patch(&mut child_scene);
         parent_scene.push_child(child_scene);
     }
+
+    /// Hot patches the components of the entity and reconciles its children with the child scenes.
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        if !I::IS_EMPTY {
+            // Inherited scenes are constructed dynamically, which spawns the children again
+            let id = context.id;
+            context.world.entity_mut(id).despawn_descendants();
+            self.construct(context)?;
+            let children = context
+                .world
+                .get::<Children>(id)
+                .map(|children| children.to_vec())
+                .unwrap_or_default();
+            record_spawned_children(context.world, id, children);
+            return Ok(());
+        }
+
+        let id = context.id;
+        let mut children = ReconcileChildren::new(context.world, id);
+        let mut patch = self.patch;
+        patch
+            .hot_patch(context)
+            .and_then(|()| self.children.reconstruct_children(context, &mut children))
+            .map_err(|error| error.with_context(ConstructErrorContext::Entity(id)))?;
+        children.finish(context.world, id);
+
+        Ok(())
+    }
+}
+
+/// Constructs the scene only if `Some`.
+impl<S: Scene> Scene for Option<S> {
+    const IS_FRAGMENT: bool = S::IS_FRAGMENT;
+
+    fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Some(scene) => scene.construct(context),
+            None => Ok(()),
+        }
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Some(scene) => scene.spawn(context),
+            None => Ok(()),
+        }
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        if let Some(inner) = self {
+            inner.dynamic_patch(scene);
+        }
+    }
+
+    fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
+        if let Some(inner) = self {
+            inner.dynamic_patch_as_child(scene);
+        }
+    }
+
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Some(scene) => scene.reconstruct(context),
+            None => Ok(()),
+        }
+    }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        match self {
+            Some(scene) => scene.reconstruct_child(context, children),
+            None => Ok(()),
+        }
+    }
+
+    fn key(&self) -> Option<&SceneKey> {
+        self.as_ref().and_then(Scene::key)
+    }
+
+    fn is_fragment(&self) -> bool {
+        self.as_ref().map_or(S::IS_FRAGMENT, Scene::is_fragment)
+    }
+}
+
+/// Constructs the scene of the active branch.
+impl<L: Scene, R: Scene> Scene for Either<L, R> {
+    const IS_FRAGMENT: bool = L::IS_FRAGMENT && R::IS_FRAGMENT;
+
+    fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Either::Left(scene) => scene.construct(context),
+            Either::Right(scene) => scene.construct(context),
+        }
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Either::Left(scene) => scene.spawn(context),
+            Either::Right(scene) => scene.spawn(context),
+        }
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        match self {
+            Either::Left(inner) => inner.dynamic_patch(scene),
+            Either::Right(inner) => inner.dynamic_patch(scene),
+        }
+    }
+
+    fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
+        match self {
+            Either::Left(inner) => inner.dynamic_patch_as_child(scene),
+            Either::Right(inner) => inner.dynamic_patch_as_child(scene),
+        }
+    }
+
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Either::Left(scene) => scene.reconstruct(context),
+            Either::Right(scene) => scene.reconstruct(context),
+        }
+    }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        match self {
+            Either::Left(scene) => scene.reconstruct_child(context, children),
+            Either::Right(scene) => scene.reconstruct_child(context, children),
+        }
+    }
+
+    fn key(&self) -> Option<&SceneKey> {
+        match self {
+            Either::Left(scene) => scene.key(),
+            Either::Right(scene) => scene.key(),
+        }
+    }
+
+    fn is_fragment(&self) -> bool {
+        match self {
+            Either::Left(scene) => scene.is_fragment(),
+            Either::Right(scene) => scene.is_fragment(),
+        }
+    }
+}
+
+/// Constructs the scene of the active branch.
+impl<S: Scene> Scene for Conditional<S> {
+    const IS_FRAGMENT: bool = S::IS_FRAGMENT;
+
+    fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.value.construct(context)
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.value.spawn(context)
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        self.value.dynamic_patch(scene);
+    }
+
+    fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
+        self.value.dynamic_patch_as_child(scene);
+    }
+
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.value.reconstruct(context)
+    }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        self.value.reconstruct_child(context, children)
+    }
+
+    fn key(&self) -> Option<&SceneKey> {
+        self.value.key()
+    }
+
+    fn is_fragment(&self) -> bool {
+        self.value.is_fragment()
+    }
 }
 
 /// Extension trait implementing [`Scene`] utilities for [`ConstructContext`].
@@ -164,23 +556,279 @@ pub trait ConstructContextSceneExt {
 
     /// Spawns a [`Scene`] under the context entity recursively.
     fn spawn_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError>;
+
+    /// Reconstructs a [`Scene`] on the context entity, reusing the children spawned by a previous construction.
+    ///
+    /// See [`Scene::reconstruct`] and [`ReconcileChildren`].
+    fn reconstruct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError>;
 }
 
+/// Construction through these methods is atomic: if it fails, the changes made by the scene are rolled back.
+/// See [`SceneRollback`].
 impl<'a> ConstructContextSceneExt for ConstructContext<'a> {
+    /// The entity becomes the root of a [`SceneInstance`](crate::SceneInstance) of the scene.
+    fn construct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError> {
+        let rollback = SceneRollback::new(self.world, self.id);
+        if let Err(error) = scene.construct(self) {
+            rollback.rollback(self.world);
+            return Err(error);
+        }
+        let components = rollback.added_components(self.world);
+        let children = rollback.spawned_children(self.world);
+        let children = children.into_iter().map(|child| (self.id, child)).collect();
+        record_scene_instance(self.world, self.id, components, children);
+        Ok(self)
+    }
+
+    /// Each spawned child becomes the root of a [`SceneInstance`](crate::SceneInstance).
+    fn spawn_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError> {
+        let rollback = SceneRollback::new(self.world, self.id);
+        if let Err(error) = scene.spawn(self) {
+            rollback.rollback(self.world);
+            return Err(error);
+        }
+        let roots = rollback.spawned_children(self.world);
+        record_spawned_instances(self.world, roots);
+        Ok(self)
+    }
+
+    /// The reused descendants are patched in place, so they're rolled back along with the entity.
+    fn reconstruct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError> {
+        let rollbacks = hierarchy_rollbacks(self.world, self.id);
+        self.world
+            .get_resource_or_insert_with(ReconstructSpawned::default)
+            .0
+            .push(Vec::new());
+        let result = scene.reconstruct(self);
+        let spawned = self
+            .world
+            .resource_mut::<ReconstructSpawned>()
+            .0
+            .pop()
+            .unwrap_or_default();
+        if let Err(error) = result {
+            for (_, child) in spawned {
+                if let Ok(child) = self.world.get_entity_mut(child) {
+                    child.despawn_recursive();
+                }
+            }
+            for rollback in rollbacks {
+                rollback.rollback(self.world);
+            }
+            return Err(error);
+        }
+        refresh_scene_instance(self.world, self.id, spawned);
+        Ok(self)
+    }
+}
+
+/// Records a [`SceneRollback`] of the entity and of each of its descendants.
+fn hierarchy_rollbacks(world: &World, id: Entity) -> Vec<SceneRollback> {
+    let mut rollbacks = Vec::new();
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+        stack.extend(world.get::<Children>(id).into_iter().flatten());
+        rollbacks.push(SceneRollback::new(world, id));
+    }
+    rollbacks
+}
+
+/// Makes each of the spawned entities the root of a [`SceneInstance`](crate::SceneInstance) of its components and descendants.
+fn record_spawned_instances(world: &mut World, roots: Vec<Entity>) {
+    for root in roots {
+        let entity = world.entity(root);
+        let components = entity.archetype().components().collect();
+        let children = entity
+            .get::<Children>()
+            .into_iter()
+            .flatten()
+            .map(|child| (root, *child))
+            .collect();
+        record_scene_instance(world, root, components, children);
+    }
+}
+
+/// The components and children of an entity before a scene is constructed on it, to undo a failed construction.
+///
+/// Rolling back despawns the children spawned since, along with their descendants, removes the added components,
+/// and restores the components that existed before from a snapshot of their values.
+/// Only components registered with [`ReflectComponent`] in the [`AppTypeRegistry`] can be restored,
+/// others keep any patched values.
+pub struct SceneRollback {
+    id: Entity,
+    components: Vec<ComponentId>,
+    snapshots: Vec<(ReflectComponent, Box<dyn PartialReflect>)>,
+    children: Vec<Entity>,
+}
+
+impl SceneRollback {
+    /// Records the current components and children of the entity, and snapshots the values of the components.
+    pub fn new(world: &World, id: Entity) -> Self {
+        let entity = world.entity(id);
+        let components = entity.archetype().components().collect::<Vec<_>>();
+
+        // The hierarchy is restored by despawning the spawned children
+        let hierarchy = [
+            world.component_id::<Parent>(),
+            world.component_id::<Children>(),
+        ];
+        let snapshots = match world.get_resource::<AppTypeRegistry>() {
+            Some(registry) => {
+                let registry = registry.read();
+                components
+                    .iter()
+                    .filter(|component| !hierarchy.contains(&Some(**component)))
+                    .filter_map(|component| {
+                        let type_id = world.components().get_info(*component)?.type_id()?;
+                        let reflect_component =
+                            registry.get_type_data::<ReflectComponent>(type_id)?;
+                        let value = reflect_component.reflect(entity)?.clone_value();
+                        Some((reflect_component.clone(), value))
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        Self {
+            id,
+            components,
+            snapshots,
+            children: entity
+                .get::<Children>()
+                .map(|children| children.to_vec())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The children spawned under the entity since the rollback was recorded.
+    pub fn spawned_children(&self, world: &World) -> Vec<Entity> {
+        world
+            .get::<Children>(self.id)
+            .into_iter()
+            .flatten()
+            .filter(|child| !self.children.contains(child))
+            .copied()
+            .collect()
+    }
+
+    /// The components added to the entity since the rollback was recorded.
+    pub fn added_components(&self, world: &World) -> Vec<ComponentId> {
+        let Ok(entity) = world.get_entity(self.id) else {
+            return Vec::new();
+        };
+        entity
+            .archetype()
+            .components()
+            .filter(|component| !self.components.contains(component))
+            .collect()
+    }
+
+    /// Despawns the children and removes the components added to the entity since the rollback was recorded,
+    /// and restores the changed components from their snapshots.
+    pub fn rollback(self, world: &mut World) {
+        if world.get_entity(self.id).is_err() {
+            return;
+        }
+        for child in self.spawned_children(world) {
+            world.entity_mut(child).despawn_recursive();
+        }
+
+        let added = self.added_components(world);
+        let mut entity = world.entity_mut(self.id);
+        for component in added {
+            entity.remove_by_id(component);
+        }
+
+        if self.snapshots.is_empty() {
+            return;
+        }
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let mut entity = world.entity_mut(self.id);
+        for (reflect_component, snapshot) in self.snapshots {
+            let unchanged = reflect_component
+                .reflect(&entity)
+                .and_then(|value| value.reflect_partial_eq(snapshot.as_ref()))
+                .unwrap_or(false);
+            if !unchanged {
+                reflect_component.insert(&mut entity, snapshot.as_ref(), &registry);
+            }
+        }
+    }
+}
+
+/// Extension trait implementing [`Scene`] utilities for [`World`].
+pub trait WorldSceneExt {
+    /// Spawns a new entity and constructs the [`Scene`] on it, returning the entity.
+    fn spawn_scene(&mut self, scene: impl Scene) -> Result<Entity, ConstructError>;
+}
+
+impl WorldSceneExt for World {
+    fn spawn_scene(&mut self, scene: impl Scene) -> Result<Entity, ConstructError> {
+        let id = self.spawn_empty().id();
+        if let Err(error) = (ConstructContext { id, world: self }).construct_scene(scene) {
+            self.entity_mut(id).despawn_recursive();
+            return Err(error);
+        }
+        Ok(id)
+    }
+}
+
+/// Extension trait implementing [`Scene`] utilities for [`EntityWorldMut`].
+pub trait EntityWorldMutSceneExt {
+    /// Constructs a [`Scene`], inserts the components to the entity, and recursively spawns the descendants.
+    fn construct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError>;
+
+    /// Spawns a [`Scene`] under the entity recursively.
+    fn spawn_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError>;
+
+    /// Reconstructs a [`Scene`] on the entity, reusing the children spawned by a previous construction.
+    fn reconstruct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError>;
+}
+
+impl<'w> EntityWorldMutSceneExt for EntityWorldMut<'w> {
     fn construct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError> {
-        scene.construct(self)?;
+        let id = self.id();
+        self.world_scope(|world| {
+            ConstructContext { id, world }
+                .construct_scene(scene)
+                .map(|_| ())
+        })?;
         Ok(self)
     }
 
     fn spawn_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError> {
-        scene.spawn(self)?;
+        let id = self.id();
+        self.world_scope(|world| {
+            ConstructContext { id, world }
+                .spawn_scene(scene)
+                .map(|_| ())
+        })?;
+        Ok(self)
+    }
+
+    fn reconstruct_scene(&mut self, scene: impl Scene) -> Result<&mut Self, ConstructError> {
+        let id = self.id();
+        self.world_scope(|world| {
+            ConstructContext { id, world }
+                .reconstruct_scene(scene)
+                .map(|_| ())
+        })?;
         Ok(self)
     }
 }
 
-struct ConstructSceneCommand<S>(S)
+struct ConstructSceneCommand<S>
 where
-    S: Scene + Send + 'static;
+    S: Scene + Send + 'static,
+{
+    scene: S,
+    /// Whether errors are logged instead of panicking, see [`SceneErrorPolicy`](crate::SceneErrorPolicy).
+    fallible: bool,
+    /// Whether the entity was spawned for the scene, and is despawned if the construction fails.
+    spawned: bool,
+}
 
 impl<S> EntityCommand for ConstructSceneCommand<S>
 where
@@ -188,22 +836,57 @@ where
 {
     fn apply(self, id: Entity, world: &mut World) {
         let mut context = ConstructContext { id, world };
-        self.0
-            .construct(&mut context)
-            .expect("failed to spawn_scene in ConstructSceneCommand");
+        if let Err(error) = context.construct_scene(self.scene) {
+            if self.spawned {
+                world.entity_mut(id).despawn_recursive();
+            }
+            handle_command_error(world, id, error, self.fallible);
+        }
     }
 }
 
 /// Extension trait implementing [`Scene`] utilities for [`EntityCommands`].
 pub trait EntityCommandsSceneExt {
     /// Constructs a [`Scene`] and applies it to the entity.
-    fn construct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands;
+    ///
+    /// Errors are handled according to the [`SceneErrorPolicy`](crate::SceneErrorPolicy) resource.
+    fn construct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
+
+    /// Same as [`EntityCommandsSceneExt::construct_scene`], but logs errors instead of panicking.
+    fn try_construct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
+
+    /// Reconstructs a [`Scene`] on the entity, reusing the children spawned by a previous construction.
+    ///
+    /// See [`ConstructContextSceneExt::reconstruct_scene`].
+    fn reconstruct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
 }
 
 impl<'w> EntityCommandsSceneExt for EntityCommands<'w> {
     // type Out = EntityCommands;
-    fn construct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands {
-        self.queue(ConstructSceneCommand(scene));
+    fn construct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        self.queue(ConstructSceneCommand {
+            scene,
+            fallible: false,
+            spawned: false,
+        });
+        self.reborrow()
+    }
+
+    fn try_construct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        self.queue(ConstructSceneCommand {
+            scene,
+            fallible: true,
+            spawned: false,
+        });
+        self.reborrow()
+    }
+
+    fn reconstruct_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        self.queue(move |id: Entity, world: &mut World| {
+            if let Err(error) = (ConstructContext { id, world }).reconstruct_scene(scene) {
+                handle_command_error(world, id, error, false);
+            }
+        });
         self.reborrow()
     }
 }
@@ -211,26 +894,176 @@ impl<'w> EntityCommandsSceneExt for EntityCommands<'w> {
 /// Scene spawning extension.
 pub trait SpawnSceneExt {
     /// Spawn the given [`Scene`].
-    fn spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands;
+    ///
+    /// Errors are handled according to the [`SceneErrorPolicy`](crate::SceneErrorPolicy) resource,
+    /// after despawning the spawned entity.
+    fn spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
+
+    /// Same as [`SpawnSceneExt::spawn_scene`], but logs errors instead of panicking.
+    fn try_spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
 }
 
 impl<'w> SpawnSceneExt for Commands<'w, '_> {
     /// Spawn the given [`Scene`].
-    fn spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands {
+    fn spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
         let mut entity = self.spawn_empty();
-        entity.queue(ConstructSceneCommand(scene));
+        entity.queue(ConstructSceneCommand {
+            scene,
+            fallible: false,
+            spawned: true,
+        });
         entity
     }
-}
 
-impl<'w> SpawnSceneExt for ChildBuilder<'w> {
-    fn spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands {
+    fn try_spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
         let mut entity = self.spawn_empty();
-        entity.queue(ConstructSceneCommand(scene));
+        entity.queue(ConstructSceneCommand {
+            scene,
+            fallible: true,
+            spawned: true,
+        });
         entity
     }
 }
 
+impl<'w> SpawnSceneExt for ChildBuilder<'w> {
+    /// Spawn the given [`Scene`] as a child.
+    ///
+    /// A [`Fragment`] is constructed on a placeholder entity, which is replaced by its children
+    /// once it has been added to the parent. The returned commands target that placeholder.
+    fn spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        spawn_child_scene(self, scene, false)
+    }
+
+    fn try_spawn_scene(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        spawn_child_scene(self, scene, true)
+    }
+}
+
+fn spawn_child_scene<'a, S: Scene + Send + 'static>(
+    builder: &'a mut ChildBuilder,
+    scene: S,
+    fallible: bool,
+) -> EntityCommands<'a> {
+    let mut entity = builder.spawn_empty();
+    if scene.is_fragment() {
+        entity.observe(|trigger: Trigger<OnAdd, Parent>, mut commands: Commands| {
+            let placeholder = trigger.entity();
+            commands.queue(move |world: &mut World| splice_fragment(world, placeholder));
+        });
+    }
+    // The child is only added to the parent after the command, so it can't be despawned on failure
+    entity.queue(ConstructSceneCommand {
+        scene,
+        fallible,
+        spawned: false,
+    });
+    entity
+}
+
+/// Moves the children of a fragment placeholder into its parent, at its position, and despawns it.
+///
+/// Each spliced child becomes the root of a [`SceneInstance`](crate::SceneInstance), as if spawned on its own.
+fn splice_fragment(world: &mut World, placeholder: Entity) {
+    let Some(parent) = world.get::<Parent>(placeholder).map(Parent::get) else {
+        return;
+    };
+    let roots = world
+        .get::<Children>(placeholder)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    let index = world
+        .get::<Children>(parent)
+        .and_then(|children| children.iter().position(|&child| child == placeholder))
+        .unwrap_or_default();
+
+    world.entity_mut(parent).insert_children(index, &roots);
+    world.entity_mut(placeholder).despawn_recursive();
+    record_spawned_instances(world, roots);
+}
+
+/// A scene without an entity of its own, spliced into its parent: `bsn! { <> [A, B] }`.
+///
+/// Spawning it spawns each child scene under the context entity, alongside the siblings of the fragment.
+/// Constructing it on an entity spawns the child scenes under that entity.
+pub struct Fragment<C: SceneTuple> {
+    /// Child scenes spliced into the parent.
+    pub children: C,
+}
+
+impl<C: SceneTuple> Scene for Fragment<C> {
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        let id = context.id;
+        let mut children = ReconcileChildren::new(context.world, id);
+        self.children.reconstruct_children(context, &mut children)?;
+        children.finish(context.world, id);
+        Ok(())
+    }
+
+    /// Reconciles the child scenes with the children of the parent, alongside the siblings of the fragment.
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        self.children.reconstruct_children(context, children)
+    }
+
+    const IS_FRAGMENT: bool = true;
+
+    fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.children.spawn_children(context)
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.children.spawn_children(context)
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        self.children.push_dynamic_children(scene);
+    }
+
+    fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
+        self.children.push_dynamic_children(scene);
+    }
+}
+
+/// A [`SceneTuple`] used as a single [`Scene`].
+///
+/// `bsn!` nests children and inherited scenes in it past the size limit of [`SceneTuple`] tuples.
+/// Constructing it applies each scene to the context entity, and spawning it spawns each scene as a child.
+pub struct NestedScenes<T: SceneTuple>(pub T);
+
+impl<T: SceneTuple> Scene for NestedScenes<T> {
+    fn construct(mut self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        let mut dynamic_scene = DynamicScene::default();
+        #[cfg(feature = "hot_reload")]
+        dynamic_scene.provenance.inherit_sources(context.world);
+        self.0.dynamic_patch(&mut dynamic_scene);
+        dynamic_scene.construct(context)
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.0.spawn_children(context)
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        self.0.dynamic_patch(scene);
+    }
+
+    fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
+        self.0.push_dynamic_children(scene);
+    }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        self.0.reconstruct_children(context, children)
+    }
+}
+
 /// For spawning scene children with an iterator.
 pub struct SceneIter<I> {
     iter: I,
@@ -257,6 +1090,11 @@ impl<S: Scene, I: Iterator<Item = S>> Scene for SceneIter<I> {
 
     fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
         for scene in self.iter {
+            if scene.is_fragment() {
+                scene.spawn(context)?;
+                continue;
+            }
+
             let id = context.world.spawn_empty().id();
             context.world.entity_mut(context.id).add_child(id);
 
@@ -277,9 +1115,301 @@ impl<S: Scene, I: Iterator<Item = S>> Scene for SceneIter<I> {
 
     fn dynamic_patch_as_child(&mut self, dynamic_scene: &mut DynamicScene) {
         for mut scene in &mut self.iter {
-            let mut child_scene = DynamicScene::default();
+            let mut child_scene = dynamic_scene.new_child();
             scene.dynamic_patch(&mut child_scene);
             dynamic_scene.push_child(child_scene);
         }
     }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        for scene in self.iter {
+            scene.reconstruct_child(context, children)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{Children, Component, Events, Reflect, World};
+
+    use super::*;
+    use crate::{bsn, Construct, ConstructPatchExt, SceneConstructFailed, SceneErrorPolicy};
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Score(u32);
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    struct Inventory {
+        items: Vec<String>,
+    }
+
+    #[derive(Component, Reflect, Default, Clone)]
+    struct Part<const N: usize>;
+
+    #[derive(Component, Reflect, Clone)]
+    struct Broken;
+
+    impl Construct for Broken {
+        type Props = ();
+
+        fn construct(_: &mut ConstructContext, _: ()) -> Result<Self, ConstructError> {
+            Err(ConstructError::InvalidProps {
+                message: "broken".into(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_conditional_scenes() {
+        let mut world = World::new();
+        let scene = |high: bool| {
+            bsn! { (if high { Score(1u32) } else { Broken }, if !high { Broken }) [
+                match high { true => Score(2u32), false => Broken }
+            ] }
+        };
+
+        // Only the active branches are constructed, and an `if` without `else` inserts nothing when not taken
+        let id = world.spawn_scene(scene(true)).unwrap();
+        assert_eq!(world.get::<Score>(id), Some(&Score(1)));
+        let child = world.get::<Children>(id).unwrap()[0];
+        assert_eq!(world.get::<Score>(child), Some(&Score(2)));
+
+        assert!(world.spawn_scene(scene(false)).is_err());
+    }
+
+    #[test]
+    fn test_reconstruct_appends() {
+        let mut world = World::new();
+        let scene = || bsn! { Inventory { items += ["sword", "shield"] } };
+
+        // The items are appended to the default list each time, not to the props retained from the last patch
+        let id = world.spawn_scene(scene()).unwrap();
+        world.entity_mut(id).reconstruct_scene(scene()).unwrap();
+        world.entity_mut(id).reconstruct_scene(scene()).unwrap();
+        assert_eq!(
+            world.get::<Inventory>(id).unwrap().items,
+            ["sword", "shield"]
+        );
+    }
+
+    #[test]
+    fn test_large_tuples() {
+        let mut world = World::new();
+
+        // Longer than the largest tuple implementing the scene and patch traits
+        let id = world
+            .spawn_scene(bsn! {
+                (Part::<0>, Part::<1>, Part::<2>, Part::<3>, Part::<4>, Part::<5>, Part::<6>, Part::<7>, Part::<8>, Part::<9>, Part::<10>, Part::<11>, Part::<12>, Part::<13>) [
+                    Score(0u32), Score(1u32), Score(2u32), Score(3u32), Score(4u32), Score(5u32), Score(6u32), Score(7u32), Score(8u32), Score(9u32), Score(10u32), Score(11u32), Score(12u32), Score(13u32)
+                ]
+            })
+            .unwrap();
+        let part = core::any::type_name::<Part<0>>().trim_end_matches("<0>");
+        let parts = world
+            .inspect_entity(id)
+            .filter(|component| component.name().starts_with(part))
+            .count();
+        assert_eq!(parts, 14);
+        let scores = world
+            .get::<Children>(id)
+            .unwrap()
+            .iter()
+            .map(|child| world.get::<Score>(*child).unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(scores, (0..14).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_world_spawn_scene() {
+        let mut world = World::new();
+
+        let id = world
+            .spawn_scene(bsn! { Score(1u32) [ Score(2u32) ] })
+            .unwrap();
+        assert_eq!(world.get::<Score>(id), Some(&Score(1)));
+        assert_eq!(world.get::<Children>(id).map(|c| c.len()), Some(1));
+
+        world
+            .entity_mut(id)
+            .spawn_scene(bsn! { Score(3u32) })
+            .unwrap();
+        assert_eq!(world.get::<Children>(id).map(|c| c.len()), Some(2));
+
+        assert!(world.spawn_scene(bsn! { Broken }).is_err());
+        assert!(world
+            .entity_mut(id)
+            .construct_scene(bsn! { Broken })
+            .is_err());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let mut world = World::new();
+        let error = world
+            .spawn_scene(bsn! { Score(1u32) [ (Score(2u32), Broken) ] })
+            .unwrap_err();
+
+        assert!(matches!(
+            error.root_cause(),
+            ConstructError::InvalidProps { .. }
+        ));
+        let context = error.context();
+        assert!(matches!(
+            context[0],
+            ConstructErrorContext::Component(component) if component.ends_with("Broken")
+        ));
+        assert!(matches!(context[1], ConstructErrorContext::Entity(_)));
+        assert!(matches!(context[2], ConstructErrorContext::Entity(_)));
+        #[cfg(feature = "hot_reload")]
+        assert!(matches!(
+            context.last(),
+            Some(ConstructErrorContext::Invocation { file, .. }) if file.ends_with("entity_patch.rs")
+        ));
+    }
+
+    #[test]
+    fn test_rollback_failed_scene() {
+        let mut world = World::new();
+        let id = world.spawn_empty().id();
+        let entity_count = world.entities().len();
+
+        let mut entity = world.entity_mut(id);
+        let result =
+            entity.construct_scene(bsn! { Score(1u32) [ Score(2u32), (Score(3u32), Broken) ] });
+        assert!(result.is_err());
+        assert_eq!(world.entities().len(), entity_count);
+        assert!(world.get::<Score>(id).is_none());
+        assert!(world.get::<Children>(id).is_none());
+    }
+
+    #[test]
+    fn test_rollback_restores_components() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Score>();
+
+        let id = world.spawn(Score(7)).id();
+        assert!(world
+            .entity_mut(id)
+            .construct_scene(bsn! { (Score(1u32), Broken) })
+            .is_err());
+        assert_eq!(world.get::<Score>(id), Some(&Score(7)));
+
+        // The entities spawned by failed commands are despawned
+        world.insert_resource(SceneErrorPolicy::Log);
+        let entity_count = world.entities().len();
+        let id = world
+            .commands()
+            .spawn_scene(bsn! { Score(1u32) [ Broken ] })
+            .id();
+        world.flush();
+        assert!(world.get_entity(id).is_err());
+        assert_eq!(world.entities().len(), entity_count);
+    }
+
+    #[test]
+    fn test_rollback_failed_reconstruct() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Score>();
+
+        let id = world
+            .spawn_scene(bsn! { Score(1u32) [ Score(2u32) ] })
+            .unwrap();
+        let child = world.get::<Children>(id).unwrap()[0];
+        let entity_count = world.entities().len();
+
+        // Fails in the reused child, after patching both entities and spawning a grandchild
+        assert!(world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! {
+                Score(3u32) [ Score(4u32) [ Score(5u32), Broken ], Score(6u32) ]
+            })
+            .is_err());
+        assert_eq!(world.entities().len(), entity_count);
+        assert_eq!(world.get::<Score>(id), Some(&Score(1)));
+        assert_eq!(world.get::<Children>(id).unwrap().to_vec(), [child]);
+        assert_eq!(world.get::<Score>(child), Some(&Score(2)));
+        assert!(world.get::<Children>(child).is_none());
+    }
+
+    #[test]
+    fn test_scene_error_policy() {
+        let mut world = World::new();
+        world.init_resource::<Events<SceneConstructFailed>>();
+        world.insert_resource(SceneErrorPolicy::Event);
+
+        let id = world.commands().spawn_scene(bsn! { Broken }).id();
+        world.flush();
+
+        let events = world.resource::<Events<SceneConstructFailed>>();
+        let failed = events.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].entity, id);
+
+        // Try variants don't panic, neither with the default policy nor with an explicit `Panic`
+        world.remove_resource::<SceneErrorPolicy>();
+        world.commands().try_spawn_scene(bsn! { Broken });
+        world.flush();
+
+        world.insert_resource(SceneErrorPolicy::Panic);
+        world.commands().try_spawn_scene(bsn! { Broken });
+        world.flush();
+    }
+
+    fn score_scene(score: u32) -> Box<dyn DynScene + Send> {
+        match score {
+            0 => bsn! { Score(0u32) }.boxed(),
+            _ => bsn! { Score(score) [ Score(0u32) ] }.boxed(),
+        }
+    }
+
+    fn child_scores(world: &World, id: Entity) -> Vec<u32> {
+        world
+            .get::<Children>(id)
+            .into_iter()
+            .flatten()
+            .map(|child| world.get::<Score>(*child).unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn test_boxed_scenes() {
+        let mut world = World::new();
+
+        let id = world.spawn_scene(score_scene(1)).unwrap();
+        assert_eq!(world.get::<Score>(id), Some(&Score(1)));
+        assert_eq!(child_scores(&world, id), [0]);
+
+        let id = world
+            .spawn_scene(bsn! { Score(5u32) [ ..(0..3).map(score_scene) ] })
+            .unwrap();
+        assert_eq!(child_scores(&world, id), [0, 1, 2]);
+
+        // Boxed fragments are still spliced into the parent
+        let fragment = || bsn! { <> [ Score(3u32), Score(4u32) ] }.boxed();
+        let id = world
+            .spawn_scene(bsn! { Score(5u32) [ Score(2u32), ..[fragment()].into_iter() ] })
+            .unwrap();
+        assert_eq!(child_scores(&world, id), [2, 3, 4]);
+
+        let id = world.spawn_empty().id();
+        world.commands().entity(id).with_children(|parent| {
+            parent.spawn_scene(fragment());
+        });
+        world.flush();
+        assert_eq!(child_scores(&world, id), [3, 4]);
+    }
 }
@@ -1,9 +1,16 @@
-use core::marker::PhantomData;
+use core::{any::TypeId, marker::PhantomData};
 
 use bevy::utils::all_tuples;
-use bevy::{ecs::reflect::ReflectComponent, prelude::Component, reflect::Reflect};
+use bevy::{
+    ecs::reflect::{AppTypeRegistry, ReflectComponent},
+    prelude::{Component, World},
+    reflect::Reflect,
+};
 
-use crate::{Construct, ConstructContext, ConstructError, ConstructPatch, Patch};
+use crate::{
+    Conditional, Construct, ConstructContext, ConstructError, ConstructErrorContext,
+    ConstructPatch, Either, Patch,
+};
 
 /// Retained props to allow hot patching.
 #[derive(Component, Reflect)]
@@ -63,6 +70,33 @@ all_tuples!(
     t
 );
 
+impl<P: HotPatch> HotPatch for Option<P> {
+    fn hot_patch(&mut self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Some(patch) => patch.hot_patch(context),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<L: HotPatch, R: HotPatch> HotPatch for Either<L, R> {
+    fn hot_patch(&mut self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        match self {
+            Either::Left(patch) => patch.hot_patch(context),
+            Either::Right(patch) => patch.hot_patch(context),
+        }
+    }
+}
+
+impl<P: HotPatch> HotPatch for Conditional<P> {
+    fn hot_patch(&mut self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        #[cfg(feature = "hot_reload")]
+        crate::record_active_branch(context.world, context.id, self.branch);
+
+        self.value.hot_patch(context)
+    }
+}
+
 impl<C, F, P> HotPatch for ConstructPatch<C, F>
 where
     C: Construct<Props = P> + Component,
@@ -73,15 +107,53 @@ where
         let props = {
             let mut entity = context.world.entity_mut(context.id);
             let mut props = entity.entry::<PatchProps<C>>().or_default();
+            // The retained props still hold the items appended by the previous patch
+            if let Some(reset) = self.reset_appended {
+                reset(&mut props.props);
+            }
             self.patch(&mut props.props);
             props.clone()
         };
 
-        let component = context.construct::<C>(props.props)?;
+        let type_path = component_type_path(
+            context.world,
+            TypeId::of::<C>(),
+            core::any::type_name::<C>(),
+        );
+        let component = context.construct::<C>(props.props).map_err(|error| {
+            error.with_context(ConstructErrorContext::Component(type_path.into()))
+        })?;
 
         let mut entity = context.world.entity_mut(context.id);
         entity.insert(component);
 
+        #[cfg(feature = "hot_reload")]
+        crate::record_hot_patch(
+            context.world,
+            context.id,
+            type_path,
+            self.fields.map(|fields| fields.iter().copied()),
+        );
+
         Ok(())
     }
 }
+
+/// The reflected [`TypePath`](bevy::reflect::TypePath) of a component if it's registered, or its type name otherwise.
+///
+/// Components don't need to be reflectable to be patched, so the type path is looked up instead of required.
+pub(crate) fn component_type_path(
+    world: &World,
+    type_id: TypeId,
+    type_name: &'static str,
+) -> &'static str {
+    world
+        .get_resource::<AppTypeRegistry>()
+        .and_then(|registry| {
+            registry
+                .read()
+                .get(type_id)
+                .map(|registration| registration.type_info().type_path())
+        })
+        .unwrap_or(type_name)
+}
@@ -1,4 +1,16 @@
 //! Hot reload
+mod provenance;
+#[cfg(feature = "remote")]
+mod remote;
+mod testing;
+mod write_back;
+
+pub use provenance::*;
+#[cfg(feature = "remote")]
+pub use remote::*;
+pub use testing::*;
+pub use write_back::*;
+
 use core::{
     hash::{Hash, Hasher},
     iter,
@@ -13,18 +25,22 @@ use bevy::{
     ecs::system::SystemState,
     prelude::*,
     reflect::{
-        DynamicEnum, DynamicStruct, DynamicTuple, DynamicTupleStruct, DynamicVariant, ReflectKind,
-        TypeInfo, TypeRegistry,
+        DynamicEnum, DynamicList, DynamicStruct, DynamicTuple, DynamicTupleStruct, DynamicVariant,
+        GetPath, ListInfo, ReflectKind, ReflectMut, ReflectRef, TypeInfo, TypeRegistry,
     },
     utils::{AHasher, HashMap, HashSet, Hashed},
 };
 use cant_wait_for_bsn_parse::*;
-use syn::{spanned::Spanned, visit::Visit, Expr, FieldValue, Member};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, visit::Visit, Expr, ExprArray, ExprMacro, FieldValue,
+    Member, Token,
+};
 use thiserror::Error;
 use visit::BsnMacroVisitor;
 
 use crate::{
-    ConstructContext, ConstructError, DynamicScene, ReflectConstruct, ReflectFromBsn, Scene,
+    ConstructContext, ConstructError, ConstructErrorContext, ConstructPatch, DynamicPatch,
+    DynamicScene, ReconcileChildren, ReflectConstruct, ReflectFromBsn, Scene, SceneKey, SceneRef,
 };
 
 /// Extension trait for [`App`] to add hot-reload sources for BSN macros.
@@ -213,6 +229,26 @@ impl AssetLoader for HotBsnMacroLoader {
 #[derive(Default, Component, Deref, DerefMut, Reflect)]
 pub struct HotReloadScenes(pub HashSet<BsnInvocationId>);
 
+/// Branches taken by the conditional entries of the hot-reloadable scenes that have been constructed on this entity.
+///
+/// Maps invocation ids to the indices of the active branches, in the order the conditional patches were applied.
+#[derive(Default, Component, Deref, DerefMut, Reflect)]
+pub struct ActiveBranches(pub HashMap<BsnInvocationId, Vec<usize>>);
+
+/// Records the active branch of a conditional patch for the bsn! invocation currently being constructed.
+pub(crate) fn record_active_branch(world: &mut World, entity: Entity, branch: usize) {
+    let Some(invocation) = world
+        .get_resource::<PatchSourceStack>()
+        .and_then(|stack| stack.0.last())
+        .map(|source| source.invocation)
+    else {
+        return;
+    };
+    let mut entity = world.entity_mut(entity);
+    let mut active_branches = entity.entry::<ActiveBranches>().or_default();
+    active_branches.entry(invocation).or_default().push(branch);
+}
+
 /// A hot-reloadable scene originating from a bsn! macro invocation.
 pub struct HotReloadableBsnMacro<T: Scene> {
     /// Source file path of this macro invocation.
@@ -227,40 +263,112 @@ pub struct HotReloadableBsnMacro<T: Scene> {
     pub scene: T,
 }
 
+impl<T: Scene> HotReloadableBsnMacro<T> {
+    fn source(&self) -> PatchSource {
+        PatchSource {
+            invocation: self.id,
+            file: self.file.into(),
+            line: self.line,
+            column: self.column,
+            inherit_depth: 0,
+        }
+    }
+
+    /// Adds the id to the entity's tracked hot-reloadable scenes, and clears the branches recorded by earlier constructions.
+    fn track(&self, context: &mut ConstructContext) {
+        let mut entity = context.world.entity_mut(context.id);
+        let mut hot_scenes = entity.entry::<HotReloadScenes>().or_default();
+        hot_scenes.insert(self.id);
+        if let Some(mut active_branches) = entity.get_mut::<ActiveBranches>() {
+            active_branches.remove(&self.id);
+        }
+    }
+
+    /// Runs `f` with this invocation as the current [`PatchSource`] of the world.
+    ///
+    /// Errors are given the location of the invocation as context.
+    fn with_source(
+        self,
+        context: &mut ConstructContext,
+        f: impl FnOnce(T, &mut ConstructContext) -> Result<(), ConstructError>,
+    ) -> Result<(), ConstructError> {
+        let source = self.source();
+        context
+            .world
+            .get_resource_or_insert_with(PatchSourceStack::default)
+            .0
+            .push(source);
+        let invocation = ConstructErrorContext::Invocation {
+            file: self.file.into(),
+            line: self.line,
+            column: self.column,
+        };
+        let result = f(self.scene, context).map_err(|error| error.with_context(invocation));
+        context.world.resource_mut::<PatchSourceStack>().0.pop();
+        result
+    }
+}
+
 impl<T: Scene> Scene for HotReloadableBsnMacro<T> {
+    const IS_FRAGMENT: bool = T::IS_FRAGMENT;
+
     fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
         info!(
             "CONSTRUCTING: file: {}, line: {}, column: {}, id: {:?}",
             self.file, self.line, self.column, self.id
         );
 
-        {
-            // Add the id to the entity's tracked hot-reloadable scenes
-            let mut entity = context.world.entity_mut(context.id);
-            let mut hot_scenes = entity.entry::<HotReloadScenes>().or_default();
-            hot_scenes.insert(self.id);
-        }
+        self.track(context);
 
         // TODO: Use the id to look up if we should use a hot-reloaded scene or the original one
         // Use original for now:
-        self.scene.construct(context)?;
+        self.with_source(context, Scene::construct)
+    }
 
-        Ok(())
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        self.track(context);
+        self.with_source(context, Scene::reconstruct)
+    }
+
+    fn reconstruct_child(
+        self,
+        context: &mut ConstructContext,
+        children: &mut ReconcileChildren,
+    ) -> Result<(), ConstructError> {
+        if self.is_fragment() {
+            return self.with_source(context, |scene, context| {
+                scene.reconstruct_child(context, children)
+            });
+        }
+        // Let the reconciler pick the child, so it's tracked by `construct` or `reconstruct`
+        children.reconstruct_child(self, context)
+    }
+
+    fn key(&self) -> Option<&SceneKey> {
+        self.scene.key()
+    }
+
+    fn is_fragment(&self) -> bool {
+        self.scene.is_fragment()
     }
 
     fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
         warn!("TODO: Hot-reloading scenes are not supported for spawning yet.");
-        self.scene.spawn(context)
+        self.with_source(context, Scene::spawn)
     }
 
     fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
         warn!("TODO: Hot-reloading scenes are not supported for dynamic_patch yet.");
+        scene.provenance.push_source(self.source());
         self.scene.dynamic_patch(scene);
+        scene.provenance.pop_source();
     }
 
     fn dynamic_patch_as_child(&mut self, scene: &mut DynamicScene) {
         warn!("TODO: Hot-reloading scenes are not supported for dynamic_patch_as_child yet.");
+        scene.provenance.push_source(self.source());
         self.scene.dynamic_patch_as_child(scene);
+        scene.provenance.pop_source();
     }
 }
 
@@ -367,35 +475,24 @@ fn hot_reload_bsn(
                                 }
                             };
 
-                            // Find any entities currently using this invocation
-                            let entities = world.query::<(Entity, &HotReloadScenes)>()
-                                .iter(world)
-                                .filter_map(|(entity, hot_scenes)| {
-                                    if hot_scenes.contains(invocation_id) {
-                                        return Some(entity);
-                                    }
-                                    None
-                                })
-                                .collect::<Vec<_>>();
-
-                            // Hot-Patch those entities
-                            for entity in entities {
-                                // TODO: Not really necessary to do this dynamicscene tango for _each_ entity? Should DynamicScene::construct take &self instead?
-                                let mut dynamic_scene = DynamicScene::default();
-                                {
-                                    let app_type_registry = world.resource::<AppTypeRegistry>();
-                                    let registry = app_type_registry.read();
-                                    add_parsed_patch_to_dynamic_scene(&mut dynamic_scene, &bsn.patch, &registry);
-                                }
+                            let span = invocation.span();
+                            let source = PatchSource {
+                                invocation: *invocation_id,
+                                file: file.path.clone().into(),
+                                line: span.start().line as u32,
+                                column: (span.start().column + 1) as u32, // TODO: UTF-8 and stuff
+                                inherit_depth: 0,
+                            };
 
-                                if let Err(e) = dynamic_scene.construct(&mut ConstructContext {
+                            // Hot-Patch any entities currently using this invocation
+                            for entity in entities_with_invocation(world, *invocation_id) {
+                                hot_patch_entity_components(
                                     world,
-                                    id: entity,
-                                }) {
-                                    error!("Failed to hot-patch entity: {:?}", e);
-                                } else {
-                                    info!("Hot-patched entity: {:?}", entity);
-                                }
+                                    entity,
+                                    &bsn.patch,
+                                    Some(source.clone()),
+                                );
+                                info!("Hot-patched entity: {:?}", entity);
                             }
 
                             // Store it for future construction of the patch.
@@ -412,25 +509,317 @@ fn hot_reload_bsn(
     });
 }
 
+/// Returns the entities that have constructed the scene of the given bsn! invocation.
+pub(crate) fn entities_with_invocation(world: &mut World, id: BsnInvocationId) -> Vec<Entity> {
+    world
+        .query::<(Entity, &HotReloadScenes)>()
+        .iter(world)
+        .filter_map(|(entity, hot_scenes)| hot_scenes.contains(&id).then_some(entity))
+        .collect()
+}
+
+/// Hot-patches an entity with the scene of `hot_patch_scene`, constructing each component on its own, logging and skipping
+/// the ones that fail so a bad edit to one component doesn't drop the rest of the hot patch.
+fn hot_patch_entity_components(
+    world: &mut World,
+    entity: Entity,
+    patch: &BsnPatch,
+    source: Option<PatchSource>,
+) {
+    let mut dynamic_scene = hot_patch_scene(world, entity, patch, source);
+    let scenes = dynamic_scene
+        .component_props
+        .drain()
+        .map(|(type_id, patches)| {
+            let mut scene = DynamicScene {
+                provenance: dynamic_scene.provenance.take_component(type_id),
+                ..Default::default()
+            };
+            scene.component_props.insert(type_id, patches);
+            if let Some(fields) = dynamic_scene.appended_fields.remove(&type_id) {
+                scene.appended_fields.insert(type_id, fields);
+            }
+            scene
+        })
+        .collect::<Vec<_>>();
+
+    for scene in scenes {
+        if let Err(error) = scene.construct(&mut ConstructContext { world, id: entity }) {
+            error!("Failed to hot-patch component, skipping it: {error}");
+        }
+    }
+}
+
+/// Builds the [`DynamicScene`] hot-patching an existing entity with a parsed [`BsnPatch`].
+///
+/// The patched fields are attributed to `source` in the [`PatchProvenance`] of the entity, if given.
+/// Conditional patches with non-literal conditions use the [`ActiveBranches`] recorded for the source invocation.
+pub(crate) fn hot_patch_scene(
+    world: &mut World,
+    entity: Entity,
+    patch: &BsnPatch,
+    source: Option<PatchSource>,
+) -> DynamicScene {
+    let active_branches = source
+        .as_ref()
+        .and_then(|source| {
+            world
+                .get::<ActiveBranches>(entity)?
+                .get(&source.invocation)
+                .cloned()
+        })
+        .unwrap_or_default();
+
+    // TODO: Not really necessary to do this dynamicscene tango for _each_ entity? Should DynamicScene::construct take &self instead?
+    let mut dynamic_scene = DynamicScene::default();
+    if let Some(source) = source {
+        dynamic_scene.provenance.push_source(source);
+    }
+    {
+        let app_type_registry = world.resource::<AppTypeRegistry>();
+        let registry = app_type_registry.read();
+        add_parsed_patch_to_dynamic_scene(
+            &mut dynamic_scene,
+            patch,
+            &registry,
+            &mut active_branches.into_iter(),
+        );
+    }
+    dynamic_scene
+}
+
+/// Builds a [`DynamicScene`] from a parsed [`BsnEntity`], including its children.
+///
+/// Component paths are resolved through the type registry, the same way as for hot reload.
+pub fn dynamic_scene_from_bsn(bsn: &BsnEntity, registry: &TypeRegistry) -> DynamicScene {
+    let mut dynamic_scene = DynamicScene::default();
+
+    if !bsn.inherits.is_empty() {
+        warn!("Inheritance is not supported for parsed bsn yet. Skipping inherited scenes.");
+    }
+
+    add_parsed_patch_to_dynamic_scene(&mut dynamic_scene, &bsn.patch, registry, &mut iter::empty());
+    if let Some(ref_name) = &bsn.ref_name {
+        let ref_name = ref_name.to_string();
+        ConstructPatch::new_inferred(move |props: &mut SceneRef| {
+            *props = SceneRef::new(ref_name.clone());
+        })
+        .dynamic_patch(&mut dynamic_scene);
+    }
+    if let Some(key) = &bsn.key {
+        match scene_key_from_expr(key) {
+            Some(key) => ConstructPatch::new_inferred(move |props: &mut SceneKey| {
+                *props = key.clone();
+            })
+            .dynamic_patch(&mut dynamic_scene),
+            None => warn!(
+                "Can't hot reload non-literal key: `{:?}`. Skipping key.",
+                key
+            ),
+        }
+    }
+    push_bsn_children(&mut dynamic_scene, bsn, registry);
+
+    dynamic_scene
+}
+
+/// Evaluates a literal `#[key(..)]` expression.
+fn scene_key_from_expr(expr: &Expr) -> Option<SceneKey> {
+    let Expr::Lit(lit) = expr else {
+        return None;
+    };
+    match &lit.lit {
+        syn::Lit::Str(lit) => Some(SceneKey::new(lit.value())),
+        syn::Lit::Char(lit) => Some(SceneKey::new(lit.value())),
+        syn::Lit::Int(lit) => Some(SceneKey::new(lit.base10_digits())),
+        _ => None,
+    }
+}
+
+/// Pushes the children of a parsed [`BsnEntity`] to a [`DynamicScene`], splicing in the children of fragments.
+fn push_bsn_children(dynamic_scene: &mut DynamicScene, bsn: &BsnEntity, registry: &TypeRegistry) {
+    for child in bsn.children.iter() {
+        match child {
+            BsnChildren::Entity(child) => push_bsn_child(dynamic_scene, child, registry),
+            BsnChildren::Spread(e) => {
+                warn!("Can't spread expression: `{:?}`. Skipping children.", e);
+            }
+            BsnChildren::Conditional(conditional) => match literal_branch(conditional) {
+                Some(branch) => {
+                    if let Some(child) = conditional.branch(branch) {
+                        push_bsn_child(dynamic_scene, child, registry);
+                    }
+                }
+                None => warn!(
+                    "Can't hot reload conditional children with non-literal conditions. Skipping children."
+                ),
+            },
+        }
+    }
+}
+
+fn push_bsn_child(dynamic_scene: &mut DynamicScene, child: &BsnEntity, registry: &TypeRegistry) {
+    if child.fragment {
+        push_bsn_children(dynamic_scene, child, registry);
+    } else {
+        dynamic_scene.push_child(dynamic_scene_from_bsn(child, registry));
+    }
+}
+
+/// Returns the index of the active branch of a conditional entry whose conditions are literals.
+///
+/// For an `if` without `else`, the index is one past the last branch when no branch is taken.
+fn literal_branch<T>(conditional: &BsnConditional<T>) -> Option<usize> {
+    match conditional {
+        BsnConditional::If(branches) => {
+            for (index, (condition, _)) in branches.iter().enumerate() {
+                match condition {
+                    Some(condition) if !literal_bool(condition)? => {}
+                    _ => return Some(index),
+                }
+            }
+            Some(branches.len())
+        }
+        BsnConditional::Match(expr, arms) => {
+            let value = literal_value(expr)?;
+            for (index, arm) in arms.iter().enumerate() {
+                if !literal_pat_matches(&arm.pat, &value)? {
+                    continue;
+                }
+                let guard = match &arm.guard {
+                    Some(guard) => literal_bool(guard)?,
+                    None => true,
+                };
+                if guard {
+                    return Some(index);
+                }
+            }
+            None
+        }
+    }
+}
+
+/// A literal value of a condition.
+#[derive(PartialEq)]
+enum LiteralValue {
+    Bool(bool),
+    Int(i128),
+    Char(char),
+    Str(String),
+}
+
+fn literal_value(expr: &Expr) -> Option<LiteralValue> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            syn::Lit::Bool(lit) => Some(LiteralValue::Bool(lit.value)),
+            syn::Lit::Int(lit) => lit.base10_parse().ok().map(LiteralValue::Int),
+            syn::Lit::Char(lit) => Some(LiteralValue::Char(lit.value())),
+            syn::Lit::Str(lit) => Some(LiteralValue::Str(lit.value())),
+            _ => None,
+        },
+        Expr::Paren(paren) => literal_value(&paren.expr),
+        Expr::Group(group) => literal_value(&group.expr),
+        Expr::Unary(unary) => match (unary.op, literal_value(&unary.expr)?) {
+            (syn::UnOp::Not(_), LiteralValue::Bool(value)) => Some(LiteralValue::Bool(!value)),
+            (syn::UnOp::Neg(_), LiteralValue::Int(value)) => Some(LiteralValue::Int(-value)),
+            _ => None,
+        },
+        Expr::Binary(binary) => {
+            let left = literal_value(&binary.left)?;
+            let right = literal_value(&binary.right)?;
+            let value = match (binary.op, left, right) {
+                (syn::BinOp::And(_), LiteralValue::Bool(l), LiteralValue::Bool(r)) => l && r,
+                (syn::BinOp::Or(_), LiteralValue::Bool(l), LiteralValue::Bool(r)) => l || r,
+                (syn::BinOp::Eq(_), l, r) => l == r,
+                (syn::BinOp::Ne(_), l, r) => l != r,
+                (syn::BinOp::Lt(_), LiteralValue::Int(l), LiteralValue::Int(r)) => l < r,
+                (syn::BinOp::Le(_), LiteralValue::Int(l), LiteralValue::Int(r)) => l <= r,
+                (syn::BinOp::Gt(_), LiteralValue::Int(l), LiteralValue::Int(r)) => l > r,
+                (syn::BinOp::Ge(_), LiteralValue::Int(l), LiteralValue::Int(r)) => l >= r,
+                _ => return None,
+            };
+            Some(LiteralValue::Bool(value))
+        }
+        _ => None,
+    }
+}
+
+fn literal_bool(expr: &Expr) -> Option<bool> {
+    match literal_value(expr)? {
+        LiteralValue::Bool(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Returns whether the pattern of a `match` arm matches a literal value, if it can tell.
+fn literal_pat_matches(pat: &syn::Pat, value: &LiteralValue) -> Option<bool> {
+    match pat {
+        syn::Pat::Wild(_) => Some(true),
+        syn::Pat::Ident(ident) if ident.subpat.is_none() => Some(true),
+        syn::Pat::Lit(lit) => Some(literal_value(&Expr::Lit(lit.clone()))? == *value),
+        syn::Pat::Paren(paren) => literal_pat_matches(&paren.pat, value),
+        syn::Pat::Or(or) => {
+            for case in or.cases.iter() {
+                if literal_pat_matches(case, value)? {
+                    return Some(true);
+                }
+            }
+            Some(false)
+        }
+        _ => None,
+    }
+}
+
+/// Skips the recorded branches of the conditional patches nested in a patch that is not applied.
+fn skip_active_branches(patch: &BsnPatch, active_branches: &mut impl Iterator<Item = usize>) {
+    match patch {
+        BsnPatch::Tuple(tuple) => {
+            for patch in tuple {
+                skip_active_branches(patch, active_branches);
+            }
+        }
+        BsnPatch::Conditional(conditional) => {
+            if let Some(body) = active_branches
+                .next()
+                .and_then(|branch| conditional.branch(branch))
+            {
+                skip_active_branches(body, active_branches);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Joins the segments of a parsed patch path, to be resolved as a short type path.
+pub(crate) fn patch_path_to_string(path: &syn::Path) -> String {
+    // TODO: Better path build (generics etc)
+    iter::once("".to_string())
+        .filter(|_| path.leading_colon.is_some())
+        .chain(path.segments.iter().map(|seg| seg.ident.to_string()))
+        .collect::<Vec<String>>()
+        .join("::")
+}
+
+/// Adds a parsed patch to a [`DynamicScene`].
+///
+/// `active_branches` are the branches recorded when the scene was constructed, in the order of its conditional patches.
+/// They are used for conditions that can't be evaluated from the source.
 fn add_parsed_patch_to_dynamic_scene(
     dynamic_scene: &mut DynamicScene,
     patch: &BsnPatch,
     registry: &TypeRegistry,
+    active_branches: &mut impl Iterator<Item = usize>,
 ) {
     // TODO: Resolve paths using reflection and parsed use declarations
     match patch {
         BsnPatch::Tuple(tuple) => {
             for patch in tuple {
-                add_parsed_patch_to_dynamic_scene(dynamic_scene, patch, registry);
+                add_parsed_patch_to_dynamic_scene(dynamic_scene, patch, registry, active_branches);
             }
         }
-        BsnPatch::Patch(path, props) => {
-            // TODO: Better path build (generics etc)
-            let path = iter::once("".to_string())
-                .filter(|_| path.leading_colon.is_some())
-                .chain(path.segments.iter().map(|seg| seg.ident.to_string()))
-                .collect::<Vec<String>>()
-                .join("::");
+        BsnPatch::Patch(path, props) | BsnPatch::Replace(path, props) => {
+            let replace = matches!(patch, BsnPatch::Replace(..));
+            let path = patch_path_to_string(path);
 
             // TODO: Better path resolution. Could parse the use declarations and avoid ambiguities.
             let Some(component_type) = registry.get_with_short_type_path(&path) else {
@@ -438,7 +827,17 @@ fn add_parsed_patch_to_dynamic_scene(
                 return;
             };
 
-            if props.is_empty() {
+            let fields = props
+                .iter()
+                .map(|(member, _)| member.to_string())
+                .collect::<Vec<_>>();
+            dynamic_scene.provenance.record(
+                component_type.type_id(),
+                component_type.type_info().type_path(),
+                (!replace).then(|| fields.iter().map(String::as_str)),
+            );
+
+            if props.is_empty() && !replace {
                 dynamic_scene
                     .component_props
                     .insert(component_type.type_id(), Vec::new());
@@ -463,93 +862,284 @@ fn add_parsed_patch_to_dynamic_scene(
                 props_type
             };
 
-            // TODO: Ugly ugly
-            // TODO: Support construct props properly
-            if props_type.type_info().kind() == ReflectKind::Struct {
-                let props_struct = props_type.type_info().as_struct().unwrap();
-                let mut dynamic_props = DynamicStruct::default();
-
-                for (member, val) in props.iter() {
-                    let Member::Named(name) = member else {
-                        warn!("Got tuple struct for `{}` which is supposed to be a struct with named fields. Skipping hot-reload for this component.", props_type.type_info().type_path());
-                        return;
-                    };
-                    let name = name.to_string();
-
-                    let Some(field) = props_struct.field(&name) else {
-                        warn!("Failed to resolve field `{}` in `{}`. Skipping hot-reload for this field.", name, props_type.type_info().type_path());
-                        continue;
-                    };
-
-                    let val = match reflect_from_bsn_expr(
-                        val.into(),
-                        field.type_info().unwrap(),
-                        registry,
-                    ) {
-                        Ok(val) => val,
-                        Err(e) => {
-                            warn!("Failed to reflect field `{}` in `{}`: {}. Skipping hot-reload for this field.", name, props_type.type_info().type_path(), e);
-                            continue;
-                        }
-                    };
+            let mut field_patches = Vec::new();
+            add_field_patches(
+                &mut field_patches,
+                props_type.type_info(),
+                "",
+                props,
+                registry,
+            );
 
-                    dynamic_props.insert_boxed(name, val.into_partial_reflect());
-                }
+            let appended_fields = field_patches
+                .iter()
+                .filter_map(|field_patch| match field_patch {
+                    FieldPatch::Append(path, _) => Some(path.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if !appended_fields.is_empty() {
+                dynamic_scene
+                    .appended_fields
+                    .insert(component_type.type_id(), appended_fields);
+            }
 
-                dynamic_scene.component_props.insert(
-                    component_type.type_id(),
-                    vec![Box::new(move |patch_props: &mut dyn Reflect| {
-                        patch_props.apply(&dynamic_props);
-                    })],
-                );
-            } else {
-                let props_struct = props_type.type_info().as_tuple_struct().unwrap();
-                let mut dynamic_props = DynamicTupleStruct::default();
-
-                for (member, val) in props.iter() {
-                    let Member::Unnamed(index) = member else {
-                        warn!("Got struct with named fields for `{}` which is supposed to be a tuple struct. Skipping hot-reload for this component.", props_type.type_info().type_path());
-                        return;
-                    };
-                    let index = index.index as usize;
-
-                    let Some(field) = props_struct.field_at(index) else {
-                        warn!("Failed to resolve field `{}` in `{}`. Skipping hot-reload for this field.", index, props_type.type_info().type_path());
-                        continue;
-                    };
-
-                    let val = match reflect_from_bsn_expr(
-                        val.into(),
-                        field.type_info().unwrap(),
-                        registry,
-                    ) {
-                        Ok(val) => val,
-                        Err(e) => {
-                            warn!("Failed to reflect field `{}` in `{}`: {}. Skipping hot-reload for this field.", index, props_type.type_info().type_path(), e);
-                            continue;
+            let reset_props = replace.then(|| reflect_construct.clone());
+            dynamic_scene.component_props.insert(
+                component_type.type_id(),
+                vec![Box::new(move |patch_props: &mut dyn Reflect| {
+                    if let Some(reflect_construct) = &reset_props {
+                        if patch_props.set(reflect_construct.default_props()).is_err() {
+                            warn!("Failed to reset props to default");
                         }
-                    };
-
-                    dynamic_props.insert_boxed(val.into_partial_reflect());
+                    }
+                    for field_patch in field_patches.iter() {
+                        field_patch.apply(patch_props);
+                    }
+                })],
+            );
+        }
+        BsnPatch::Expr(e) => {
+            warn!(
+                "Can't hot reload expression: `{:?}`. Skipping hot-reload for this component.",
+                e
+            );
+        }
+        BsnPatch::Conditional(conditional) => {
+            let recorded = active_branches.next();
+            let Some(branch) = literal_branch(conditional).or(recorded) else {
+                warn!("Can't hot reload conditional patches with non-literal conditions that were not constructed from a hot-reloadable scene. Skipping hot-reload for these components.");
+                return;
+            };
+            if let Some(recorded) = recorded.filter(|recorded| *recorded != branch) {
+                if let Some(body) = conditional.branch(recorded) {
+                    skip_active_branches(body, active_branches);
                 }
+            }
+            if let Some(body) = conditional.branch(branch) {
+                add_parsed_patch_to_dynamic_scene(dynamic_scene, body, registry, active_branches);
+            }
+        }
+    }
+}
 
-                dynamic_scene.component_props.insert(
-                    component_type.type_id(),
-                    vec![Box::new(move |patch_props: &mut dyn Reflect| {
-                        patch_props.apply(&dynamic_props);
-                    })],
+/// A change to a field of reflected props, at a path for [`GetPath`].
+enum FieldPatch {
+    /// Sets the field to the value.
+    Set(String, Box<dyn PartialReflect>),
+    /// Appends the items of the list to the list field.
+    Append(String, Box<dyn PartialReflect>),
+    /// Makes the [`ConstructProp`](crate::ConstructProp) field hold props, starting from the given default props
+    /// unless it already does, so the fields of the props can be patched in place.
+    Props(String, Box<dyn PartialReflect>),
+}
+
+impl FieldPatch {
+    fn apply(&self, props: &mut dyn Reflect) {
+        let (FieldPatch::Set(path, value)
+        | FieldPatch::Append(path, value)
+        | FieldPatch::Props(path, value)) = self;
+        let field = match props.reflect_path_mut(path.as_str()) {
+            Ok(field) => field,
+            Err(e) => {
+                warn!("Failed to patch field `{}`: {}", path, e);
+                return;
+            }
+        };
+        match self {
+            FieldPatch::Set(..) => field.apply(value.as_ref()),
+            FieldPatch::Append(..) => match (field.reflect_mut(), value.reflect_ref()) {
+                (ReflectMut::List(list), ReflectRef::List(items)) => {
+                    for item in items.iter() {
+                        list.push(item.clone_value());
+                    }
+                }
+                _ => warn!("Can't append to field `{}` which is not a list", path),
+            },
+            FieldPatch::Props(..) => {
+                let has_props = matches!(
+                    field.reflect_ref(),
+                    ReflectRef::Enum(prop) if prop.variant_name() == "Prop"
                 );
-            };
+                if !has_props {
+                    field.apply(construct_prop(value.clone_value()).as_ref());
+                }
+            }
         }
-        BsnPatch::Expr(e) => {
+    }
+}
+
+/// Resolves the parsed fields of props of the given type to [`FieldPatch`]es, at paths starting with `prefix`.
+///
+/// Nested props patch the fields of the inner props in place, instead of replacing them.
+fn add_field_patches(
+    field_patches: &mut Vec<FieldPatch>,
+    ty: &TypeInfo,
+    prefix: &str,
+    fields: &[(BsnMemberPath, BsnProp)],
+    registry: &TypeRegistry,
+) {
+    for (member, val) in fields.iter() {
+        let Some((field, reflect_path)) = resolve_member_path(ty, member) else {
             warn!(
-                "Can't hot reload expression: `{:?}`. Skipping hot-reload for this component.",
+                "Failed to resolve field `{}` in `{}`. Skipping hot-reload for this field.",
+                member,
+                ty.type_path()
+            );
+            continue;
+        };
+        let reflect_path = format!("{prefix}{reflect_path}");
+
+        let result = match val {
+            BsnProp::Props(props) => {
+                nested_props(props, field, registry).map(|(default_props, props_type)| {
+                    field_patches.push(FieldPatch::Props(reflect_path.clone(), default_props));
+                    // The props are the first field of `ConstructProp::Prop`
+                    let prefix = format!("{reflect_path}.0");
+                    add_field_patches(field_patches, props_type, &prefix, &props.fields, registry);
+                })
+            }
+            BsnProp::Append(_) => reflect_from_bsn_prop(val, field, registry)
+                .map(|val| field_patches.push(FieldPatch::Append(reflect_path, val))),
+            _ => reflect_from_bsn_prop(val, field, registry)
+                .map(|val| field_patches.push(FieldPatch::Set(reflect_path, val))),
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to reflect field `{}` in `{}`: {}. Skipping hot-reload for this field.",
+                member,
+                ty.type_path(),
                 e
             );
         }
     }
 }
 
+/// Reflects a [`BsnProp`] for a field of the given type.
+///
+/// For [`ConstructProp`](crate::ConstructProp) fields, `@` props are wrapped in
+/// [`ConstructProp::Prop`](crate::ConstructProp::Prop). Nested props are resolved by [`add_field_patches`].
+fn reflect_from_bsn_prop(
+    prop: &BsnProp,
+    ty: &TypeInfo,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn PartialReflect>, FromBsnError> {
+    match (prop, construct_prop_props_type(ty)) {
+        (BsnProp::Value(expr) | BsnProp::Append(expr), _) | (BsnProp::Prop(expr), None) => {
+            reflect_from_bsn_expr(expr, ty, registry)
+        }
+        (BsnProp::Prop(expr), Some(props_type)) => Ok(construct_prop(reflect_from_bsn_expr(
+            expr, props_type, registry,
+        )?)),
+        (BsnProp::Props(_), _) => Err(FromBsnError::Custom(
+            "Nested props can only patch a field".into(),
+        )),
+    }
+}
+
+/// Wraps the props in [`ConstructProp::Prop`](crate::ConstructProp::Prop).
+fn construct_prop(props: Box<dyn PartialReflect>) -> Box<dyn PartialReflect> {
+    let mut dynamic_tuple = DynamicTuple::default();
+    dynamic_tuple.insert_boxed(props);
+    Box::new(DynamicEnum::new(
+        "Prop",
+        DynamicVariant::Tuple(dynamic_tuple),
+    ))
+}
+
+/// Returns the type of the props if the type is a [`ConstructProp`](crate::ConstructProp).
+fn construct_prop_props_type(ty: &TypeInfo) -> Option<&'static TypeInfo> {
+    let TypeInfo::Enum(enum_info) = ty else {
+        return None;
+    };
+    if enum_info.type_path_table().ident() != Some("ConstructProp") {
+        return None;
+    }
+    enum_info
+        .variant("Prop")?
+        .as_tuple_variant()
+        .ok()?
+        .field_at(0)?
+        .type_info()
+}
+
+/// Resolves the default props and the props type of nested props, for a field of the given type.
+fn nested_props(
+    props: &BsnProps,
+    ty: &TypeInfo,
+    registry: &TypeRegistry,
+) -> Result<(Box<dyn PartialReflect>, &'static TypeInfo), FromBsnError> {
+    let Some(props_type) = construct_prop_props_type(ty) else {
+        return Err(FromBsnError::Custom(
+            format!(
+                "Nested props require a `ConstructProp` field, but got `{}`",
+                ty.type_path()
+            )
+            .into(),
+        ));
+    };
+    let path = patch_path_to_string(&props.path);
+    let Some(reflect_construct) = registry
+        .get_with_short_type_path(&path)
+        .and_then(|registration| registration.data::<ReflectConstruct>())
+    else {
+        return Err(FromBsnError::Custom(
+            format!("No registered ReflectConstruct for nested props `{}`", path).into(),
+        ));
+    };
+    if reflect_construct.props_type_id != props_type.type_id() {
+        return Err(FromBsnError::MismatchedType);
+    }
+    Ok((
+        reflect_construct.default_props().into_partial_reflect(),
+        props_type,
+    ))
+}
+
+/// Resolves the type of the field at the end of a member path, and the path to it for [`GetPath`].
+fn resolve_member_path<'a>(
+    ty: &'a TypeInfo,
+    path: &BsnMemberPath,
+) -> Option<(&'a TypeInfo, String)> {
+    let mut ty = ty;
+    let mut reflect_path = String::new();
+    for member in path.0.iter() {
+        ty = match (member, ty) {
+            (Member::Named(name), TypeInfo::Struct(info)) => {
+                info.field(&name.to_string())?.type_info()?
+            }
+            (Member::Unnamed(index), TypeInfo::TupleStruct(info)) => {
+                info.field_at(index.index as usize)?.type_info()?
+            }
+            (Member::Unnamed(index), TypeInfo::Tuple(info)) => {
+                info.field_at(index.index as usize)?.type_info()?
+            }
+            _ => return None,
+        };
+        reflect_path.push('.');
+        reflect_path.push_str(&BsnMemberPath::from(member.clone()).to_string());
+    }
+    Some((ty, reflect_path))
+}
+
+fn reflect_list_from_bsn_exprs<'a>(
+    elems: impl Iterator<Item = &'a Expr>,
+    ty: &TypeInfo,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn PartialReflect>, FromBsnError> {
+    let Some(item_info) = ty.as_list().ok().and_then(ListInfo::item_info) else {
+        return Err(FromBsnError::Custom(
+            format!("Missing item type info for list `{}`", ty.type_path()).into(),
+        ));
+    };
+
+    let mut dynamic_list = DynamicList::default();
+    for elem in elems {
+        dynamic_list.push_box(reflect_from_bsn_expr(elem, item_info, registry)?);
+    }
+    Ok(Box::new(dynamic_list))
+}
+
 fn reflect_from_bsn_expr(
     expr: &Expr,
     ty: &TypeInfo,
@@ -705,6 +1295,17 @@ fn reflect_from_bsn_expr(
                 "Struct-like enum not supported yet".into(),
             ));
         }
+        Expr::Array(ExprArray { elems, .. }) if kind == ReflectKind::List => {
+            reflect_list_from_bsn_exprs(elems.iter(), ty, registry)?
+        }
+        Expr::Macro(ExprMacro { mac, .. })
+            if kind == ReflectKind::List && mac.path.is_ident("vec") =>
+        {
+            let elems = mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .map_err(|e| FromBsnError::Custom(Box::new(e)))?;
+            reflect_list_from_bsn_exprs(elems.iter(), ty, registry)?
+        }
         Expr::Call(_) => {
             // TODO: FunctionRegistry
             return Err(FromBsnError::Custom(
@@ -720,3 +1321,420 @@ fn reflect_from_bsn_expr(
 
     Ok(val)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bsn, Construct, ConstructPatchExt, EntityPatch, EntityWorldMutSceneExt};
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Construct)]
+    struct Score(i32);
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Construct)]
+    struct Player {
+        name: String,
+        level: u32,
+    }
+
+    #[derive(Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Construct)]
+    struct Label {
+        text: String,
+        size: u32,
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug, Construct)]
+    #[reflect(Component)]
+    struct Badge {
+        #[construct]
+        #[construct(default = Label { text: String::new(), size: 12 })]
+        label: Label,
+        level: u32,
+    }
+
+    #[derive(Reflect, Default, Clone, PartialEq, Debug)]
+    struct Range {
+        min: i32,
+        max: i32,
+    }
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Construct)]
+    struct Stats {
+        health: Range,
+    }
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Construct)]
+    struct Inventory {
+        items: Vec<String>,
+        gold: u32,
+    }
+
+    /// Fails to construct, with [`Score`] as its props.
+    #[derive(Component, Reflect, Clone)]
+    #[reflect(Component, Construct)]
+    struct Broken;
+
+    impl Construct for Broken {
+        type Props = Score;
+
+        fn construct(_: &mut ConstructContext, _: Score) -> Result<Self, ConstructError> {
+            Err(ConstructError::InvalidProps {
+                message: "broken".into(),
+            })
+        }
+    }
+
+    fn test_app() -> HotReloadTestApp {
+        let mut test_app = HotReloadTestApp::new();
+        test_app.app.register_type::<Score>();
+        test_app.app.register_type::<Broken>();
+        test_app.app.register_type::<Player>();
+        test_app.app.register_type::<Label>();
+        test_app.app.register_type::<Stats>();
+        test_app.app.register_type::<Inventory>();
+        crate::register_derived_constructs(&mut test_app.app);
+        test_app
+    }
+
+    #[test]
+    fn test_hot_reload_patch() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            r#"fn player() -> impl Scene { bsn! { (Score(1), Player { name: "shane", level: 2 }) } }"#,
+        );
+
+        let entity = test_app.spawn_invocation(file, 0);
+        test_app.assert_component(entity, &Score(1));
+        test_app.assert_component(
+            entity,
+            &Player {
+                name: "shane".into(),
+                level: 2,
+            },
+        );
+
+        test_app.modify_file(
+            file,
+            r#"fn player() -> impl Scene { bsn! { (Score(5), Player { name: "fred" }) } }"#,
+        );
+        test_app.assert_component(entity, &Score(5));
+        test_app.assert_component(
+            entity,
+            &Player {
+                name: "fred".into(),
+                level: 2,
+            },
+        );
+    }
+
+    #[test]
+    fn test_hot_reload_skips_failed_component() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn score() -> impl Scene { bsn! { Score(1) } }",
+        );
+        let entity = test_app.spawn_invocation(file, 0);
+
+        // The rest of the patch still applies
+        test_app.modify_file(
+            file,
+            "fn score() -> impl Scene { bsn! { (Broken(2), Score(3)) } }",
+        );
+        test_app.assert_component(entity, &Score(3));
+        test_app.assert_no_component::<Broken>(entity);
+    }
+
+    #[test]
+    fn test_nested_props() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            r#"fn badge() -> impl Scene { bsn! { Badge { label: @Label { text: "gold" }, level: 2 } } }"#,
+        );
+
+        let entity = test_app.spawn_invocation(file, 0);
+        test_app.assert_component(
+            entity,
+            &Badge {
+                label: Label {
+                    text: "gold".into(),
+                    size: 12,
+                },
+                level: 2,
+            },
+        );
+    }
+
+    #[test]
+    fn test_hot_reload_member_path() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn stats() -> impl Scene { bsn! { Stats { health.max: 10 } } }",
+        );
+
+        let entity = test_app.spawn_invocation(file, 0);
+        test_app.assert_component(
+            entity,
+            &Stats {
+                health: Range { min: 0, max: 10 },
+            },
+        );
+
+        test_app.modify_file(
+            file,
+            "fn stats() -> impl Scene { bsn! { Stats { health.min: 2 } } }",
+        );
+        test_app.assert_component(
+            entity,
+            &Stats {
+                health: Range { min: 2, max: 10 },
+            },
+        );
+    }
+
+    #[test]
+    fn test_hot_reload_append_and_replace() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            r#"fn inventory() -> impl Scene { bsn! { Inventory { items: ["sword"], gold: 5 } } }"#,
+        );
+
+        let entity = test_app.spawn_invocation(file, 0);
+        // Appends are applied to the props of the scene, not to the current component
+        test_app.modify_file(
+            file,
+            r#"fn inventory() -> impl Scene { bsn! { Inventory { items += ["shield"] } } }"#,
+        );
+        test_app.assert_component(
+            entity,
+            &Inventory {
+                items: vec!["shield".into()],
+                gold: 5,
+            },
+        );
+
+        test_app.modify_file(
+            file,
+            r#"fn inventory() -> impl Scene { bsn! { Inventory { items += ["shield"], gold: 6 } } }"#,
+        );
+        test_app.assert_component(
+            entity,
+            &Inventory {
+                items: vec!["shield".into()],
+                gold: 6,
+            },
+        );
+
+        test_app.modify_file(
+            file,
+            r#"fn inventory() -> impl Scene { bsn! { =Inventory { gold: 1 } } }"#,
+        );
+        test_app.assert_component(
+            entity,
+            &Inventory {
+                items: Vec::new(),
+                gold: 1,
+            },
+        );
+    }
+
+    #[test]
+    fn test_hot_reload_literal_conditional() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn score() -> impl Scene { bsn! { (Player, if true { Score(1) } else { Score(2) }) [if false { Score(3) }, match 2 { 1 => Score(4), _ => Score(5) }] } }",
+        );
+
+        let entity = test_app.spawn_invocation(file, 0);
+        test_app.assert_component(entity, &Score(1));
+        let children = test_app.world().get::<Children>(entity).unwrap().to_vec();
+        assert_eq!(children.len(), 1);
+        test_app.assert_component(children[0], &Score(5));
+
+        test_app.modify_file(
+            file,
+            "fn score() -> impl Scene { bsn! { (Player, if false { Score(1) } else { Score(2) }) } }",
+        );
+        test_app.assert_component(entity, &Score(2));
+
+        test_app.modify_file(
+            file,
+            "fn score() -> impl Scene { bsn! { (Player, match 'b' { 'a' | 'b' => Score(6), _ => Score(7) }) } }",
+        );
+        test_app.assert_component(entity, &Score(6));
+    }
+
+    #[test]
+    fn test_hot_reload_active_branch() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn score(high: bool) -> impl Scene { bsn! { (Player, if high { Score(10) } else { Score(1) }) } }",
+        );
+
+        // The condition can't be evaluated from the source, so the branch taken on construction is hot reloaded
+        let high = false;
+        let mut scene = bsn! { (Player, if high { Score(10) } else { Score(1) }) };
+        scene.id = test_app.world().resource::<HotReloadState>().invocation_ids[&file][0];
+        let entity = test_app.world_mut().spawn_empty().id();
+        test_app
+            .world_mut()
+            .entity_mut(entity)
+            .construct_scene(scene)
+            .unwrap();
+        test_app.assert_component(entity, &Score(1));
+
+        test_app.modify_file(
+            file,
+            "fn score(high: bool) -> impl Scene { bsn! { (Player, if high { Score(10) } else { Score(2) }) } }",
+        );
+        test_app.assert_component(entity, &Score(2));
+
+        // Literal conditions override the recorded branch
+        test_app.modify_file(
+            file,
+            "fn score(high: bool) -> impl Scene { bsn! { (Player, if true { Score(10) } else { Score(2) }) } }",
+        );
+        test_app.assert_component(entity, &Score(10));
+    }
+
+    #[test]
+    fn test_reconstruct_tracks_children() {
+        let mut world = World::new();
+        let scene = |count: usize| EntityPatch {
+            inherit: (),
+            patch: (),
+            children: (bsn! { Score(1) }, (count > 1).then(|| bsn! { Score(2) })),
+        };
+        let entity = world.spawn_empty().id();
+        world.entity_mut(entity).construct_scene(scene(1)).unwrap();
+
+        // Children spawned while reconciling are tracked like constructed ones
+        world
+            .entity_mut(entity)
+            .reconstruct_scene(scene(2))
+            .unwrap();
+        let children = world.get::<Children>(entity).unwrap().to_vec();
+        assert_eq!(children.len(), 2);
+        for child in children {
+            assert!(world.get::<HotReloadScenes>(child).is_some());
+        }
+    }
+
+    #[test]
+    fn test_hot_reload_only_patches_own_invocation() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn a() -> impl Scene { bsn! { Score(1) } }\nfn b() -> impl Scene { bsn! { Score(2) } }",
+        );
+
+        let a = test_app.spawn_invocation(file, 0);
+        let b = test_app.spawn_invocation(file, 1);
+
+        test_app.modify_file(
+            file,
+            "fn a() -> impl Scene { bsn! { Score(3) } }\nfn b() -> impl Scene { bsn! { Score(2) } }",
+        );
+        test_app.assert_component(a, &Score(3));
+        test_app.assert_component(b, &Score(2));
+    }
+
+    #[test]
+    fn test_hot_reload_skips_changed_invocation_count() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn a() -> impl Scene { bsn! { Score(1) } }",
+        );
+
+        let entity = test_app.spawn_invocation(file, 0);
+
+        test_app.modify_file(
+            file,
+            "fn a() -> impl Scene { bsn! { (Score(3), Player { level: 2 }) } }\nfn b() -> impl Scene { bsn! { Score(2) } }",
+        );
+        test_app.assert_component(entity, &Score(1));
+        test_app.assert_no_component::<Player>(entity);
+    }
+
+    #[test]
+    fn test_write_back_field() {
+        let mut test_app = test_app();
+        let path = env::temp_dir().join(format!("bsn_write_back_{}.rs", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let file = test_app.add_file(
+            path.clone(),
+            "fn player() -> impl Scene {\n    bsn! { (Score(1), Player { level: 2 }, Player { name: \"a\" }) }\n}\n",
+        );
+        let entity = test_app.spawn_invocation(file, 0);
+
+        {
+            let mut player = test_app.world_mut().get_mut::<Player>(entity).unwrap();
+            player.level = 7;
+            player.name = "fred".into();
+        }
+        test_app
+            .world_mut()
+            .write_back_bsn_component_field::<Player>(entity, "level")
+            .unwrap();
+        test_app
+            .world_mut()
+            .write_back_bsn_component_field::<Player>(entity, "name")
+            .unwrap();
+        test_app.update();
+
+        // Fields are written to the last patch that sets them
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            content,
+            "fn player() -> impl Scene {\n    bsn! { (Score(1), Player { level: 7 }, Player { name: \"fred\" }) }\n}\n"
+        );
+        test_app.assert_component(
+            entity,
+            &Player {
+                name: "fred".into(),
+                level: 7,
+            },
+        );
+    }
+
+    #[test]
+    fn test_write_back_ambiguous_insert() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn a() -> impl Scene { bsn! { Player { level: 1 } } }\nfn b() -> impl Scene { bsn! { Player { level: 2 } } }",
+        );
+        let entity = test_app.spawn_invocation(file, 0);
+        let other = test_app.world().resource::<HotReloadState>().invocation_ids[&file][1];
+        test_app
+            .world_mut()
+            .get_mut::<HotReloadScenes>(entity)
+            .unwrap()
+            .insert(other);
+
+        // Neither invocation sets the field, so there's no telling which one it belongs to
+        let error = test_app
+            .world_mut()
+            .write_back_bsn_component_field::<Player>(entity, "name")
+            .unwrap_err();
+        assert!(matches!(error, BsnWriteBackError::AmbiguousInsert(..)));
+
+        let error = test_app
+            .world_mut()
+            .write_back_bsn_component_field::<Player>(entity, "level")
+            .unwrap_err();
+        assert!(matches!(error, BsnWriteBackError::Ambiguous(..)));
+    }
+}
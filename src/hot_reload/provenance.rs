@@ -0,0 +1,364 @@
+//! Provenance tracking: which bsn! invocation set each component field.
+use alloc::borrow::Cow;
+use core::any::TypeId;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::BsnInvocationId;
+
+/// The bsn! invocation a patch originates from.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct PatchSource {
+    /// ID of the bsn! invocation.
+    pub invocation: BsnInvocationId,
+    /// Source file of the bsn! invocation.
+    pub file: Cow<'static, str>,
+    /// Line number (1-based) of the bsn! invocation.
+    pub line: u32,
+    /// Column number (1-based) of the bsn! invocation.
+    pub column: u32,
+    /// Number of inheritance levels the patch was applied through. `0` is the scene of the entity itself.
+    pub inherit_depth: u32,
+}
+
+/// Records which [`PatchSource`] won for each patched component and field of this entity.
+///
+/// Components are keyed by their reflected [`TypePath`], the same for static and dynamic scenes.
+/// Components that aren't registered in the type registry are keyed by their type name instead.
+#[derive(Component, Default, Debug, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct PatchProvenance(pub HashMap<String, ComponentProvenance>);
+
+impl PatchProvenance {
+    /// Returns the source that won for the field of the component with the given type path.
+    pub fn field(&self, component: &str, field: &str) -> Option<&PatchSource> {
+        self.0.get(component)?.field(field)
+    }
+
+    /// Records a patch of the component with the given type path. `None` fields means the whole component was replaced.
+    pub fn record<'a>(
+        &mut self,
+        component: &str,
+        source: &PatchSource,
+        fields: Option<impl IntoIterator<Item = &'a str>>,
+    ) {
+        self.0
+            .entry(component.to_string())
+            .or_default()
+            .record(source, fields);
+    }
+}
+
+/// Provenance of a single component.
+#[derive(Default, Debug, Clone, Reflect)]
+pub struct ComponentProvenance {
+    /// The last source that patched the component.
+    pub last: Option<PatchSource>,
+    /// The last source that replaced the component as a whole.
+    pub replaced: Option<PatchSource>,
+    /// The last source that set each field, since the component was last replaced.
+    pub fields: HashMap<String, PatchSource>,
+}
+
+impl ComponentProvenance {
+    /// Returns the source that won for the given field.
+    ///
+    /// Nested fields are given as dotted paths, e.g. `translation.x`, and fall back to their parent fields.
+    pub fn field(&self, field: &str) -> Option<&PatchSource> {
+        let mut field = field;
+        loop {
+            if let Some(source) = self.fields.get(field) {
+                return Some(source);
+            }
+            match field.rsplit_once('.') {
+                Some((parent, _)) => field = parent,
+                None => return self.replaced.as_ref(),
+            }
+        }
+    }
+
+    fn record<'a>(
+        &mut self,
+        source: &PatchSource,
+        fields: Option<impl IntoIterator<Item = &'a str>>,
+    ) {
+        self.last = Some(source.clone());
+        match fields {
+            Some(fields) => {
+                for field in fields {
+                    // Setting a field overrides everything previously set inside of it
+                    let nested = format!("{field}.");
+                    self.fields.retain(|key, _| !key.starts_with(&nested));
+                    self.fields.insert(field.to_string(), source.clone());
+                }
+            }
+            None => {
+                self.replaced = Some(source.clone());
+                self.fields.clear();
+            }
+        }
+    }
+}
+
+/// Stack of the sources of the bsn! invocations currently being constructed.
+#[derive(Resource, Default)]
+pub(crate) struct PatchSourceStack(pub(crate) Vec<PatchSource>);
+
+/// Records the patches of a [`DynamicScene`](crate::DynamicScene) until it's constructed.
+#[derive(Default)]
+pub(crate) struct ProvenanceRecorder {
+    sources: Vec<PatchSource>,
+    inherit_depth: u32,
+    records: Vec<(TypeId, &'static str, PatchSource, Option<Vec<String>>)>,
+}
+
+impl ProvenanceRecorder {
+    /// Continues with the sources currently being constructed in the world.
+    pub(crate) fn inherit_sources(&mut self, world: &World) {
+        if let Some(stack) = world.get_resource::<PatchSourceStack>() {
+            self.sources.clone_from(&stack.0);
+        }
+    }
+
+    /// Returns a recorder for a child scene, carrying over the current sources.
+    pub(crate) fn child(&self) -> Self {
+        Self {
+            sources: self.sources.clone(),
+            inherit_depth: self.inherit_depth,
+            records: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push_source(&mut self, source: PatchSource) {
+        self.sources.push(source);
+    }
+
+    pub(crate) fn pop_source(&mut self) {
+        self.sources.pop();
+    }
+
+    pub(crate) fn enter_inherit(&mut self) {
+        self.inherit_depth += 1;
+    }
+
+    pub(crate) fn exit_inherit(&mut self) {
+        self.inherit_depth -= 1;
+    }
+
+    /// Records a patch of a component, attributed to the current source.
+    ///
+    /// The component is keyed by its type path once applied, falling back to `type_name`.
+    pub(crate) fn record<'a>(
+        &mut self,
+        component: TypeId,
+        type_name: &'static str,
+        fields: Option<impl IntoIterator<Item = &'a str>>,
+    ) {
+        let Some(source) = self.sources.last() else {
+            return;
+        };
+        let source = PatchSource {
+            inherit_depth: self.inherit_depth,
+            ..source.clone()
+        };
+        let fields = fields.map(|fields| fields.into_iter().map(ToString::to_string).collect());
+        self.records.push((component, type_name, source, fields));
+    }
+
+    /// Moves the records of `component` into a new recorder.
+    pub(crate) fn take_component(&mut self, component: TypeId) -> Self {
+        let (taken, records) = core::mem::take(&mut self.records)
+            .into_iter()
+            .partition(|(type_id, ..)| *type_id == component);
+        self.records = records;
+        Self {
+            sources: self.sources.clone(),
+            inherit_depth: self.inherit_depth,
+            records: taken,
+        }
+    }
+
+    /// Applies the recorded patches to the [`PatchProvenance`] of the entity.
+    pub(crate) fn apply(self, world: &mut World, entity: Entity) {
+        if self.records.is_empty() {
+            return;
+        }
+        let records = self
+            .records
+            .into_iter()
+            .map(|(type_id, type_name, source, fields)| {
+                let type_path = crate::component_type_path(world, type_id, type_name);
+                (type_path, source, fields)
+            })
+            .collect::<Vec<_>>();
+        let mut entity = world.entity_mut(entity);
+        let mut provenance = entity.entry::<PatchProvenance>().or_default();
+        for (component, source, fields) in records.iter() {
+            provenance.record(
+                component,
+                source,
+                fields
+                    .as_ref()
+                    .map(|fields| fields.iter().map(String::as_str)),
+            );
+        }
+    }
+}
+
+/// Records a patch applied directly to an entity, attributed to the bsn! invocation currently being constructed.
+///
+/// Patches applied outside of a bsn! invocation, with an empty [`PatchSourceStack`], have no source and aren't recorded.
+pub(crate) fn record_hot_patch<'a>(
+    world: &mut World,
+    entity: Entity,
+    component: &str,
+    fields: Option<impl IntoIterator<Item = &'a str>>,
+) {
+    let Some(source) = world
+        .get_resource::<PatchSourceStack>()
+        .and_then(|stack| stack.0.last())
+        .cloned()
+    else {
+        return;
+    };
+    let mut entity = world.entity_mut(entity);
+    let mut provenance = entity.entry::<PatchProvenance>().or_default();
+    provenance.record(component, &source, fields);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ConstructContext, ConstructPatchExt, DynamicScene, EntityPatch, HotReloadableBsnMacro,
+        ReflectConstruct, Scene,
+    };
+
+    #[derive(Component, Reflect, Default, Clone)]
+    #[reflect(Component, Construct)]
+    struct Health {
+        current: i32,
+        max: i32,
+    }
+
+    /// Its type path differs from its type name.
+    #[derive(Component, Reflect, Default, Clone)]
+    #[reflect(Component, Construct)]
+    #[type_path = "game"]
+    struct Armor(u32);
+
+    /// Not registered, so keyed by its type name rather than its type path.
+    #[derive(Component, Reflect, Default, Clone)]
+    #[type_path = "game"]
+    struct Shield(u32);
+
+    fn bsn_macro<S: Scene>(line: u32, scene: S) -> HotReloadableBsnMacro<S> {
+        HotReloadableBsnMacro {
+            file: "src/scenes.rs",
+            line,
+            column: 1,
+            id: BsnInvocationId::new("src/scenes.rs", line, 1),
+            scene,
+        }
+    }
+
+    #[test]
+    fn test_inherited_provenance() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Health>();
+
+        let max_health = bsn_macro(
+            10,
+            EntityPatch {
+                inherit: (),
+                patch: Health::patch(|props| props.max = 3000).with_fields(&["max"]),
+                children: (),
+            },
+        );
+        let player = bsn_macro(
+            1,
+            EntityPatch {
+                inherit: (max_health,),
+                patch: Health::patch(|props| props.current = 2000).with_fields(&["current"]),
+                children: (),
+            },
+        );
+
+        let entity = world.spawn_empty().id();
+        player
+            .construct(&mut ConstructContext {
+                id: entity,
+                world: &mut world,
+            })
+            .unwrap();
+
+        let provenance = world.get::<PatchProvenance>(entity).unwrap();
+        let health = Health::type_path();
+        let current = provenance.field(health, "current").unwrap();
+        assert_eq!((current.line, current.inherit_depth), (1, 0));
+        let max = provenance.field(health, "max").unwrap();
+        assert_eq!((max.line, max.inherit_depth), (10, 1));
+    }
+
+    #[test]
+    fn test_hot_patch_provenance() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Armor>();
+        let armor = |line, value| {
+            bsn_macro(
+                line,
+                EntityPatch {
+                    inherit: (),
+                    patch: Armor::patch(move |props| props.0 = value).with_fields(&["0"]),
+                    children: (),
+                },
+            )
+        };
+        let entity = world.spawn_empty().id();
+        let mut context = ConstructContext {
+            id: entity,
+            world: &mut world,
+        };
+
+        // Hot patched when constructed statically, and again when reconstructed
+        armor(1, 5).construct(&mut context).unwrap();
+        armor(2, 6).reconstruct(&mut context).unwrap();
+        let provenance = context.world.get::<PatchProvenance>(entity).unwrap();
+        assert_eq!(provenance.field("game::Armor", "0").unwrap().line, 2);
+
+        // Dynamic scenes record under the same key
+        let mut dynamic_scene = DynamicScene::default();
+        armor(3, 7).dynamic_patch(&mut dynamic_scene);
+        dynamic_scene.construct(&mut context).unwrap();
+        let provenance = world.get::<PatchProvenance>(entity).unwrap();
+        assert_eq!(provenance.len(), 1);
+        assert_eq!(provenance.field("game::Armor", "0").unwrap().line, 3);
+
+        // Components missing from the type registry fall back to their type name
+        let shield = bsn_macro(
+            4,
+            EntityPatch {
+                inherit: (),
+                patch: Shield::patch(|props| props.0 = 2).with_fields(&["0"]),
+                children: (),
+            },
+        );
+        shield
+            .construct(&mut ConstructContext {
+                id: entity,
+                world: &mut world,
+            })
+            .unwrap();
+        let provenance = world.get::<PatchProvenance>(entity).unwrap();
+        let shield = core::any::type_name::<Shield>();
+        assert_eq!(provenance.field(shield, "0").unwrap().line, 4);
+    }
+}
@@ -0,0 +1,387 @@
+//! Bevy Remote Protocol methods for pushing BSN edits into a running app.
+use bevy::{
+    prelude::*,
+    remote::{error_codes, BrpError, BrpResult, RemotePlugin},
+};
+use cant_wait_for_bsn_parse::{syn, BsnEntity};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ConstructContext, SceneRollback};
+
+use super::{
+    dynamic_scene_from_bsn, entities_with_invocation, hot_patch_scene, BsnInvocationId,
+    BsnRustFile, HotReloadState,
+};
+
+/// The method path for a `bsn/patch` request.
+pub const BSN_PATCH_METHOD: &str = "bsn/patch";
+
+/// The method path for a `bsn/spawn` request.
+pub const BSN_SPAWN_METHOD: &str = "bsn/spawn";
+
+/// The method path for a `bsn/list_invocations` request.
+pub const BSN_LIST_INVOCATIONS_METHOD: &str = "bsn/list_invocations";
+
+/// `bsn/patch`: Applies the patch of the given BSN to a set of existing entities.
+///
+/// The targets are every entity that has constructed the scene of `invocation`, plus `entity` if given.
+/// At least one target must be supplied. If patching any of them fails, none of them are patched. Children in the BSN are ignored, just like for file-based hot reload.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BsnPatchParams {
+    /// BSN text, i.e. the contents of a bsn! invocation.
+    pub bsn: String,
+    /// The bsn! invocation whose entities should be patched.
+    #[serde(default)]
+    pub invocation: Option<u64>,
+    /// A single entity to patch.
+    #[serde(default)]
+    pub entity: Option<Entity>,
+}
+
+/// The response to a `bsn/patch` request: the entities that were patched.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BsnPatchResponse {
+    /// The entities that were patched.
+    pub entities: Vec<Entity>,
+}
+
+/// `bsn/spawn`: Spawns the given BSN, including its children, as a new entity.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BsnSpawnParams {
+    /// BSN text, i.e. the contents of a bsn! invocation.
+    pub bsn: String,
+    /// Optional parent to spawn the scene under.
+    #[serde(default)]
+    pub parent: Option<Entity>,
+}
+
+/// The response to a `bsn/spawn` request.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BsnSpawnResponse {
+    /// The root entity of the spawned scene.
+    pub entity: Entity,
+}
+
+/// A hot-reloadable bsn! invocation, as listed by `bsn/list_invocations`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BsnInvocationInfo {
+    /// ID of the invocation.
+    pub id: u64,
+    /// Source file containing the invocation.
+    pub file: String,
+    /// Index of the invocation within the source file.
+    pub index: usize,
+    /// Entities that have constructed the scene of this invocation.
+    pub entities: Vec<Entity>,
+}
+
+/// The response to a `bsn/list_invocations` request.
+pub type BsnListInvocationsResponse = Vec<BsnInvocationInfo>;
+
+/// Extension trait for registering the BSN methods on the [`RemotePlugin`].
+pub trait BsnRemotePluginExt {
+    /// Adds the `bsn/patch`, `bsn/spawn` and `bsn/list_invocations` methods.
+    fn with_bsn_methods(self) -> Self;
+}
+
+impl BsnRemotePluginExt for RemotePlugin {
+    fn with_bsn_methods(self) -> Self {
+        self.with_method(BSN_PATCH_METHOD, process_bsn_patch_request)
+            .with_method(BSN_SPAWN_METHOD, process_bsn_spawn_request)
+            .with_method(
+                BSN_LIST_INVOCATIONS_METHOD,
+                process_bsn_list_invocations_request,
+            )
+    }
+}
+
+/// Handles a `bsn/patch` request coming from a client.
+pub fn process_bsn_patch_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let BsnPatchParams {
+        bsn,
+        invocation,
+        entity,
+    } = parse_params(params)?;
+    let bsn = parse_bsn(&bsn)?;
+
+    let mut entities = invocation
+        .map(|id| entities_with_invocation(world, BsnInvocationId(id)))
+        .unwrap_or_default();
+    entities.extend(entity);
+
+    if invocation.is_none() && entities.is_empty() {
+        return Err(invalid_params(
+            "either `invocation` or `entity` must be supplied",
+        ));
+    }
+
+    // Every target is resolved before patching any of them, so a failed request patches nothing
+    if let Some(missing) = entities
+        .iter()
+        .find(|entity| world.get_entity(**entity).is_err())
+    {
+        return Err(entity_not_found(*missing));
+    }
+    let scenes = entities
+        .iter()
+        .map(|entity| hot_patch_scene(world, *entity, &bsn.patch, None))
+        .collect::<Vec<_>>();
+
+    let mut rollbacks = Vec::new();
+    for (entity, scene) in entities.iter().zip(scenes) {
+        rollbacks.push(SceneRollback::new(world, *entity));
+        if let Err(error) = scene.construct(&mut ConstructContext { world, id: *entity }) {
+            // In reverse, in case an entity was targeted twice
+            for rollback in rollbacks.into_iter().rev() {
+                rollback.rollback(world);
+            }
+            return Err(internal_error(error));
+        }
+    }
+
+    serde_json::to_value(BsnPatchResponse { entities }).map_err(internal_error)
+}
+
+/// Handles a `bsn/spawn` request coming from a client.
+pub fn process_bsn_spawn_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let BsnSpawnParams { bsn, parent } = parse_params(params)?;
+    let bsn = parse_bsn(&bsn)?;
+
+    if let Some(parent) = parent {
+        if world.get_entity(parent).is_err() {
+            return Err(entity_not_found(parent));
+        }
+    }
+
+    let dynamic_scene = {
+        let app_type_registry = world.resource::<AppTypeRegistry>();
+        let registry = app_type_registry.read();
+        dynamic_scene_from_bsn(&bsn, &registry)
+    };
+
+    let entity = world.spawn_empty().id();
+    if let Some(parent) = parent {
+        world.entity_mut(parent).add_child(entity);
+    }
+
+    if let Err(error) = dynamic_scene.construct(&mut ConstructContext { world, id: entity }) {
+        world.entity_mut(entity).despawn_recursive();
+        return Err(internal_error(error));
+    }
+
+    serde_json::to_value(BsnSpawnResponse { entity }).map_err(internal_error)
+}
+
+/// Handles a `bsn/list_invocations` request coming from a client.
+pub fn process_bsn_list_invocations_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let files = {
+        let state = world.resource::<HotReloadState>();
+        let assets = world.resource::<Assets<BsnRustFile>>();
+        state
+            .invocation_ids
+            .iter()
+            .filter_map(|(asset_id, ids)| {
+                assets
+                    .get(*asset_id)
+                    .map(|file| (file.path.clone(), ids.clone()))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut response = BsnListInvocationsResponse::new();
+    for (file, ids) in files {
+        for (index, id) in ids.into_iter().enumerate() {
+            response.push(BsnInvocationInfo {
+                id: *id,
+                file: file.clone(),
+                index,
+                entities: entities_with_invocation(world, id),
+            });
+        }
+    }
+
+    serde_json::to_value(response).map_err(internal_error)
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, BrpError> {
+    let Some(params) = params else {
+        return Err(invalid_params("params not provided"));
+    };
+    serde_json::from_value(params).map_err(|e| invalid_params(e.to_string()))
+}
+
+fn parse_bsn(bsn: &str) -> Result<BsnEntity, BrpError> {
+    syn::parse_str::<BsnEntity>(bsn).map_err(|e| invalid_params(format!("invalid bsn: {e}")))
+}
+
+fn invalid_params(message: impl Into<String>) -> BrpError {
+    BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: message.into(),
+        data: None,
+    }
+}
+
+fn entity_not_found(entity: Entity) -> BrpError {
+    BrpError {
+        code: error_codes::ENTITY_NOT_FOUND,
+        message: format!("Entity {entity} does not exist"),
+        data: None,
+    }
+}
+
+fn internal_error(error: impl core::fmt::Display) -> BrpError {
+    BrpError {
+        code: error_codes::INTERNAL_ERROR,
+        message: error.to_string(),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::remote::{BrpMessage, BrpSender};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        register_reflect_from_bsn, BsnHotReloadPlugin, HotReloadTestApp, ReflectConstruct,
+    };
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Construct)]
+    struct Score(i32);
+
+    fn test_app() -> HotReloadTestApp {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            BsnHotReloadPlugin,
+            RemotePlugin::default().with_bsn_methods(),
+        ));
+        register_reflect_from_bsn(&mut app);
+        app.register_type::<Score>();
+        app.update();
+        HotReloadTestApp { app }
+    }
+
+    /// Sends a request through the [`BrpSender`], like a client would, and returns the response.
+    fn request(test_app: &mut HotReloadTestApp, method: &str, params: Value) -> BrpResult {
+        let (sender, receiver) = async_channel::bounded(1);
+        test_app
+            .world()
+            .resource::<BrpSender>()
+            .force_send(BrpMessage {
+                method: method.into(),
+                params: Some(params),
+                sender,
+            })
+            .unwrap();
+        test_app.app.update();
+        receiver.try_recv().unwrap()
+    }
+
+    #[test]
+    fn test_spawn_and_patch() {
+        let mut test_app = test_app();
+
+        let response = request(
+            &mut test_app,
+            BSN_SPAWN_METHOD,
+            json!({ "bsn": "Score(1) [Score(2)]" }),
+        )
+        .unwrap();
+        let BsnSpawnResponse { entity } = serde_json::from_value(response).unwrap();
+        test_app.assert_component(entity, &Score(1));
+        assert_eq!(test_app.world().get::<Children>(entity).unwrap().len(), 1);
+
+        let response = request(
+            &mut test_app,
+            BSN_PATCH_METHOD,
+            json!({ "bsn": "Score(3)", "entity": entity }),
+        )
+        .unwrap();
+        let BsnPatchResponse { entities } = serde_json::from_value(response).unwrap();
+        assert_eq!(entities, [entity]);
+        test_app.assert_component(entity, &Score(3));
+    }
+
+    #[test]
+    fn test_patch_invocation() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn score() -> impl Scene { bsn! { Score(1) } }",
+        );
+        let entity = test_app.spawn_invocation(file, 0);
+
+        let response = request(&mut test_app, BSN_LIST_INVOCATIONS_METHOD, Value::Null).unwrap();
+        let invocations: BsnListInvocationsResponse = serde_json::from_value(response).unwrap();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].file, "src/scenes.rs");
+        assert_eq!(invocations[0].entities, [entity]);
+
+        request(
+            &mut test_app,
+            BSN_PATCH_METHOD,
+            json!({ "bsn": "Score(4)", "invocation": invocations[0].id }),
+        )
+        .unwrap();
+        test_app.assert_component(entity, &Score(4));
+    }
+
+    #[test]
+    fn test_patch_all_or_nothing() {
+        let mut test_app = test_app();
+        let file = test_app.add_file(
+            "src/scenes.rs",
+            "fn score() -> impl Scene { bsn! { Score(1) } }",
+        );
+        let entity = test_app.spawn_invocation(file, 0);
+        let response = request(&mut test_app, BSN_LIST_INVOCATIONS_METHOD, Value::Null).unwrap();
+        let invocations: BsnListInvocationsResponse = serde_json::from_value(response).unwrap();
+
+        // The entity of the invocation isn't patched, as the other target is missing
+        let missing = test_app.world_mut().spawn_empty().id();
+        test_app.world_mut().despawn(missing);
+        let error = request(
+            &mut test_app,
+            BSN_PATCH_METHOD,
+            json!({ "bsn": "Score(4)", "invocation": invocations[0].id, "entity": missing }),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, error_codes::ENTITY_NOT_FOUND);
+        test_app.assert_component(entity, &Score(1));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let mut test_app = test_app();
+
+        let error =
+            request(&mut test_app, BSN_PATCH_METHOD, json!({ "bsn": "Score" })).unwrap_err();
+        assert_eq!(error.code, error_codes::INVALID_PARAMS);
+
+        let error =
+            request(&mut test_app, BSN_SPAWN_METHOD, json!({ "bsn": "Score(" })).unwrap_err();
+        assert_eq!(error.code, error_codes::INVALID_PARAMS);
+
+        let error = request(&mut test_app, BSN_SPAWN_METHOD, json!({ "parent": 0 })).unwrap_err();
+        assert_eq!(error.code, error_codes::INVALID_PARAMS);
+
+        let missing = test_app.world_mut().spawn_empty().id();
+        test_app.world_mut().despawn(missing);
+        let error = request(
+            &mut test_app,
+            BSN_SPAWN_METHOD,
+            json!({ "bsn": "Score(1)", "parent": missing }),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, error_codes::ENTITY_NOT_FOUND);
+    }
+}
@@ -0,0 +1,147 @@
+//! Headless test utilities for the hot-reload pipeline.
+use core::fmt::Debug;
+
+use bevy::prelude::*;
+use cant_wait_for_bsn_parse::{syn, visit::BsnMacroVisitor, BsnEntity};
+use syn::visit::Visit;
+
+use crate::{register_reflect_from_bsn, ConstructContext};
+
+use super::{
+    dynamic_scene_from_bsn, BsnHotReloadPlugin, BsnRustFile, HotReloadScenes, HotReloadState,
+};
+
+/// A headless [`App`] for testing hot reload without a file watcher.
+///
+/// Source files are inserted directly into [`Assets<BsnRustFile>`], which emits the same [`AssetEvent`]s
+/// the asset loader would when a watched file is loaded or modified.
+pub struct HotReloadTestApp {
+    /// The app under test.
+    pub app: App,
+}
+
+impl Default for HotReloadTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotReloadTestApp {
+    /// Creates an app with [`MinimalPlugins`], [`AssetPlugin`] and [`BsnHotReloadPlugin`].
+    ///
+    /// Components used in the tested sources need to be registered on [`HotReloadTestApp::app`].
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), BsnHotReloadPlugin));
+        register_reflect_from_bsn(&mut app);
+        app.update();
+        Self { app }
+    }
+
+    /// Returns the world of the app.
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// Returns the world of the app mutably.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Adds a source file, as if it had been loaded from `path`, and runs the hot-reload pipeline.
+    pub fn add_file(
+        &mut self,
+        path: impl Into<String>,
+        content: impl Into<String>,
+    ) -> AssetId<BsnRustFile> {
+        let handle = self
+            .world_mut()
+            .resource_mut::<Assets<BsnRustFile>>()
+            .add(BsnRustFile {
+                path: path.into(),
+                content: content.into(),
+            });
+        let id = handle.id();
+        self.world_mut()
+            .resource_mut::<HotReloadState>()
+            .handles
+            .insert(id, handle);
+        self.update();
+        id
+    }
+
+    /// Replaces the content of a source file, as if it had been modified on disk, and runs the hot-reload pipeline.
+    pub fn modify_file(&mut self, id: AssetId<BsnRustFile>, content: impl Into<String>) {
+        self.world_mut()
+            .resource_mut::<Assets<BsnRustFile>>()
+            .get_mut(id)
+            .expect("source file should exist")
+            .content = content.into();
+        self.update();
+    }
+
+    /// Spawns the scene of the `index`-th bsn! invocation in a source file.
+    ///
+    /// The scene is built from the source through a [`DynamicScene`](crate::DynamicScene) and tracked for hot reload
+    /// the same way as a compiled bsn! invocation, so no `bsn!` expansion is needed in tests.
+    pub fn spawn_invocation(&mut self, id: AssetId<BsnRustFile>, index: usize) -> Entity {
+        let invocation_id = self.world().resource::<HotReloadState>().invocation_ids[&id][index];
+
+        let bsn = {
+            let file = self
+                .world()
+                .resource::<Assets<BsnRustFile>>()
+                .get(id)
+                .expect("source file should exist");
+            let ast = syn::parse_file(&file.content).expect("source file should parse");
+            let mut visitor = BsnMacroVisitor::default();
+            visitor.visit_file(&ast);
+            syn::parse2::<BsnEntity>(visitor.invocations[index].tokens.clone())
+                .expect("bsn! invocation should parse")
+        };
+
+        let dynamic_scene = {
+            let app_type_registry = self.world().resource::<AppTypeRegistry>();
+            let registry = app_type_registry.read();
+            dynamic_scene_from_bsn(&bsn, &registry)
+        };
+
+        let world = self.world_mut();
+        let entity = world
+            .spawn(HotReloadScenes([invocation_id].into_iter().collect()))
+            .id();
+        dynamic_scene
+            .construct(&mut ConstructContext { id: entity, world })
+            .expect("scene should construct");
+        entity
+    }
+
+    /// Runs enough updates for asset events to reach the hot-reload systems.
+    pub fn update(&mut self) {
+        // Asset events are flushed in `Last`, and read during `Update` of the following frame.
+        self.app.update();
+        self.app.update();
+    }
+
+    /// Asserts that the entity has a component of type `C` equal to `expected`.
+    #[track_caller]
+    pub fn assert_component<C: Component + PartialEq + Debug>(&self, entity: Entity, expected: &C) {
+        match self.world().get::<C>(entity) {
+            Some(component) => assert_eq!(component, expected),
+            None => panic!(
+                "entity {entity} is missing component {}",
+                core::any::type_name::<C>()
+            ),
+        }
+    }
+
+    /// Asserts that the entity does not have a component of type `C`.
+    #[track_caller]
+    pub fn assert_no_component<C: Component>(&self, entity: Entity) {
+        assert!(
+            self.world().get::<C>(entity).is_none(),
+            "entity {entity} has unexpected component {}",
+            core::any::type_name::<C>()
+        );
+    }
+}
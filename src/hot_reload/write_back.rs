@@ -0,0 +1,533 @@
+//! Writing runtime edits back to the bsn! source.
+use core::any::TypeId;
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    reflect::{PartialReflect, ReflectRef, TypeRegistry, VariantType},
+};
+use cant_wait_for_bsn_parse::{
+    syn, visit::BsnMacroVisitor, BsnEntity, BsnMemberPath, BsnPatch, BsnProp,
+};
+use syn::{spanned::Spanned, visit::Visit, Member};
+use thiserror::Error;
+
+use crate::ReflectConstruct;
+
+use super::{patch_path_to_string, BsnInvocationId, BsnRustFile, HotReloadScenes, HotReloadState};
+
+/// Error when writing a runtime edit back to the bsn! source.
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum BsnWriteBackError {
+    /// The entity has not constructed any hot-reloadable scene.
+    #[error("entity {0} has no hot-reloadable scenes")]
+    NotTracked(Entity),
+    /// The component is not registered, or is missing reflection data.
+    #[error("component `{0}` can't be written back: {1}")]
+    Component(String, &'static str),
+    /// The component does not have the given field.
+    #[error("component `{0}` has no field `{1}`")]
+    FieldNotFound(String, String),
+    /// The field value can't be expressed as bsn.
+    #[error("value of field `{1}` in `{0}` can't be expressed as bsn")]
+    UnsupportedValue(String, String),
+    /// None of the bsn! invocations of the entity patch the component.
+    #[error("no bsn! invocation of entity {0} patches `{1}`")]
+    InvocationNotFound(Entity, String),
+    /// More than one bsn! invocation of the entity patches the field.
+    #[error("field `{1}` is patched by more than one bsn! invocation of entity {0}")]
+    Ambiguous(Entity, String),
+    /// None of the bsn! invocations of the entity set the field, and more than one patches the component.
+    #[error("field `{2}` isn't set by any bsn! invocation of entity {0}, and more than one patches `{1}`")]
+    AmbiguousInsert(Entity, String, String),
+    /// The source file path is relative, and `CARGO_MANIFEST_DIR` isn't set to resolve it.
+    #[error(
+        "can't resolve source file `{0}` without `CARGO_MANIFEST_DIR`, run the app through cargo"
+    )]
+    ManifestDirNotSet(String),
+    /// The field can't be inserted into the patch.
+    #[error("can't insert field `{1}` into the patch of `{0}`, add it to the source manually")]
+    CantInsert(String, String),
+    /// An [IO](std::io) Error
+    #[error("could not write source file: {0}")]
+    Io(#[from] std::io::Error),
+    /// A [Syn](syn) Error
+    #[error("failed to parse source file: {0}")]
+    Syn(#[from] syn::Error),
+}
+
+/// Extension trait for [`World`] to write runtime edits of components back to their bsn! source.
+pub trait BsnWriteBackExt {
+    /// Rewrites `field` of the component in the bsn! invocation that the entity originates from, using the current value of the field.
+    ///
+    /// The source file is written to disk and the hot-reload asset is updated, so the change is picked up again by hot reload.
+    /// `field` is either a field name or the index of a tuple struct field.
+    fn write_back_bsn_field(
+        &mut self,
+        entity: Entity,
+        component: TypeId,
+        field: &str,
+    ) -> Result<(), BsnWriteBackError>;
+
+    /// Typed version of [`BsnWriteBackExt::write_back_bsn_field`].
+    fn write_back_bsn_component_field<C: Component>(
+        &mut self,
+        entity: Entity,
+        field: &str,
+    ) -> Result<(), BsnWriteBackError> {
+        self.write_back_bsn_field(entity, TypeId::of::<C>(), field)
+    }
+}
+
+impl BsnWriteBackExt for World {
+    fn write_back_bsn_field(
+        &mut self,
+        entity: Entity,
+        component: TypeId,
+        field: &str,
+    ) -> Result<(), BsnWriteBackError> {
+        let member = syn::parse_str::<Member>(field)?;
+
+        let (component_path, value) = {
+            let app_type_registry = self.resource::<AppTypeRegistry>();
+            let registry = app_type_registry.read();
+            reflect_field_to_bsn(self, &registry, entity, component, &member)?
+        };
+
+        let invocation_ids = self
+            .get::<HotReloadScenes>(entity)
+            .ok_or(BsnWriteBackError::NotTracked(entity))?
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+
+        // Find the edit in each invocation the entity originates from
+        let mut edits = Vec::new();
+        {
+            let state = self.resource::<HotReloadState>();
+            let assets = self.resource::<Assets<BsnRustFile>>();
+            let app_type_registry = self.resource::<AppTypeRegistry>();
+            let registry = app_type_registry.read();
+
+            for invocation_id in invocation_ids {
+                let Some((asset_id, index)) = find_invocation(state, invocation_id) else {
+                    continue;
+                };
+                let Some(file) = assets.get(asset_id) else {
+                    continue;
+                };
+
+                let edit = find_field_edit(
+                    &file.content,
+                    index,
+                    &registry,
+                    component,
+                    &component_path,
+                    &member,
+                )?;
+                if let Some(edit) = edit {
+                    edits.push((asset_id, edit));
+                }
+            }
+        }
+
+        // Prefer invocations that already set the field over the ones that only patch the component
+        let replacing = edits
+            .iter()
+            .filter(|(_, edit)| matches!(edit, FieldEdit::Replace(..)))
+            .count();
+        let (asset_id, edit) = match (replacing, edits.len()) {
+            (0, 0) => {
+                return Err(BsnWriteBackError::InvocationNotFound(
+                    entity,
+                    component_path,
+                ));
+            }
+            (0, 1) => edits.remove(0),
+            (0, _) => {
+                return Err(BsnWriteBackError::AmbiguousInsert(
+                    entity,
+                    component_path,
+                    field.to_string(),
+                ));
+            }
+            (1, _) => edits
+                .into_iter()
+                .find(|(_, edit)| matches!(edit, FieldEdit::Replace(..)))
+                .unwrap(),
+            _ => return Err(BsnWriteBackError::Ambiguous(entity, field.to_string())),
+        };
+
+        let (path, content) = {
+            let assets = self.resource::<Assets<BsnRustFile>>();
+            let file = assets.get(asset_id).unwrap();
+            let content = edit
+                .apply(&file.content, &member, &value)
+                .ok_or_else(|| BsnWriteBackError::CantInsert(component_path, field.to_string()))?;
+            (
+                source_file_path(&file.path, env::var_os("CARGO_MANIFEST_DIR"))?,
+                content,
+            )
+        };
+
+        fs::write(&path, &content)?;
+        info!("Wrote `{}` back to {:?}", field, path);
+
+        // Update the asset right away, so hot reload picks up the change without waiting for the file watcher
+        self.resource_mut::<Assets<BsnRustFile>>()
+            .get_mut(asset_id)
+            .unwrap()
+            .content = content;
+
+        Ok(())
+    }
+}
+
+/// A 1-based line and 0-based char column in a source file.
+#[derive(Clone, Copy)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    fn start(spanned: &impl Spanned) -> Self {
+        let start = spanned.span().start();
+        Self {
+            line: start.line,
+            column: start.column,
+        }
+    }
+
+    fn end(spanned: &impl Spanned) -> Self {
+        let end = spanned.span().end();
+        Self {
+            line: end.line,
+            column: end.column,
+        }
+    }
+
+    fn start_of_prop(prop: &BsnProp) -> Self {
+        match prop {
+            BsnProp::Value(expr) | BsnProp::Prop(expr) | BsnProp::Append(expr) => Self::start(expr),
+            BsnProp::Props(props) => Self::start(&props.path),
+        }
+    }
+
+    fn end_of_prop(prop: &BsnProp) -> Self {
+        match prop {
+            BsnProp::Value(expr) | BsnProp::Prop(expr) | BsnProp::Append(expr) => Self::end(expr),
+            BsnProp::Props(props) => {
+                let end = props.brace_token.span.close().end();
+                Self {
+                    line: end.line,
+                    column: end.column,
+                }
+            }
+        }
+    }
+}
+
+/// An edit of a single field in the source of a bsn! invocation.
+enum FieldEdit {
+    /// Replace the expression of an existing field.
+    Replace(Position, Position),
+    /// Append the field after the last field of a braced patch.
+    Append(Position),
+    /// Add braces with the field after the path of a patch without fields.
+    AddBraces(Position),
+}
+
+impl FieldEdit {
+    fn apply(&self, content: &str, member: &Member, value: &str) -> Option<String> {
+        let (start, end, text) = match self {
+            FieldEdit::Replace(start, end) => (*start, *end, value.to_string()),
+            FieldEdit::Append(at) => {
+                let Member::Named(name) = member else {
+                    return None;
+                };
+                (*at, *at, format!(", {name}: {value}"))
+            }
+            FieldEdit::AddBraces(at) => {
+                let Member::Named(name) = member else {
+                    return None;
+                };
+                let start = offset(content, *at)?;
+                if content[start..].trim_start().starts_with(['{', '(']) {
+                    // An empty struct, which the parsed patch doesn't tell apart from a bare path
+                    return None;
+                }
+                (*at, *at, format!(" {{ {name}: {value} }}"))
+            }
+        };
+
+        let start = offset(content, start)?;
+        let end = offset(content, end)?;
+        let mut content = content.to_string();
+        content.replace_range(start..end, &text);
+        Some(content)
+    }
+}
+
+fn find_invocation(
+    state: &HotReloadState,
+    invocation_id: BsnInvocationId,
+) -> Option<(AssetId<BsnRustFile>, usize)> {
+    state.invocation_ids.iter().find_map(|(asset_id, ids)| {
+        ids.iter()
+            .position(|id| *id == invocation_id)
+            .map(|index| (*asset_id, index))
+    })
+}
+
+/// Finds where to write `member` of the component in the root patch of the invocation.
+///
+/// The last patch of the component that sets the field is edited, so the written value is the one that takes effect.
+/// If none do, the field is inserted into the last patch of the component.
+fn find_field_edit(
+    content: &str,
+    index: usize,
+    registry: &TypeRegistry,
+    component: TypeId,
+    component_path: &str,
+    member: &Member,
+) -> Result<Option<FieldEdit>, BsnWriteBackError> {
+    let ast = syn::parse_file(content)?;
+    let mut visitor = BsnMacroVisitor::default();
+    visitor.visit_file(&ast);
+    let Some(invocation) = visitor.invocations.get(index) else {
+        return Ok(None);
+    };
+    let bsn = syn::parse2::<BsnEntity>(invocation.tokens.clone())?;
+
+    // Only the patch of the root entity, children are other entities
+    let mut patches = Vec::new();
+    collect_patches(&bsn.patch, registry, component, &mut patches);
+
+    // The last patch setting the field wins, unless a later patch replaces the whole component
+    for (_, fields, replace) in patches.iter().rev() {
+        if let Some((_, prop)) = fields
+            .iter()
+            .rev()
+            .find(|(m, prop)| m.as_member() == Some(member) && !matches!(prop, BsnProp::Append(_)))
+        {
+            return Ok(Some(FieldEdit::Replace(
+                Position::start_of_prop(prop),
+                Position::end_of_prop(prop),
+            )));
+        }
+        if *replace {
+            break;
+        }
+    }
+
+    // Otherwise the field is inserted into the last patch
+    let Some((path, fields, _)) = patches.last() else {
+        return Ok(None);
+    };
+    match (fields.last(), member) {
+        (Some((_, prop)), Member::Named(_)) => {
+            Ok(Some(FieldEdit::Append(Position::end_of_prop(prop))))
+        }
+        (None, Member::Named(_)) => Ok(Some(FieldEdit::AddBraces(Position::end(*path)))),
+        (_, Member::Unnamed(index)) => Err(BsnWriteBackError::CantInsert(
+            component_path.to_string(),
+            index.index.to_string(),
+        )),
+    }
+}
+
+fn collect_patches<'a>(
+    patch: &'a BsnPatch,
+    registry: &TypeRegistry,
+    component: TypeId,
+    patches: &mut Vec<(&'a syn::Path, &'a Vec<(BsnMemberPath, BsnProp)>, bool)>,
+) {
+    match patch {
+        BsnPatch::Tuple(tuple) => {
+            for patch in tuple {
+                collect_patches(patch, registry, component, patches);
+            }
+        }
+        BsnPatch::Patch(path, fields) | BsnPatch::Replace(path, fields) => {
+            let resolved = registry.get_with_short_type_path(&patch_path_to_string(path));
+            if resolved.is_some_and(|registration| registration.type_id() == component) {
+                let replace = matches!(patch, BsnPatch::Replace(..));
+                patches.push((path, fields, replace));
+            }
+        }
+        BsnPatch::Expr(_) | BsnPatch::Conditional(_) => {}
+    }
+}
+
+/// Returns the short path of the component and the current value of its field as bsn.
+fn reflect_field_to_bsn(
+    world: &World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    component: TypeId,
+    member: &Member,
+) -> Result<(String, String), BsnWriteBackError> {
+    let registration = registry
+        .get(component)
+        .ok_or_else(|| BsnWriteBackError::Component(format!("{component:?}"), "not registered"))?;
+    let component_path = registration
+        .type_info()
+        .type_path_table()
+        .short_path()
+        .to_string();
+
+    // Props of other constructs can't be derived from the constructed component
+    let is_props = registration
+        .data::<ReflectConstruct>()
+        .is_some_and(|reflect_construct| reflect_construct.props_type_id == component);
+    if !is_props {
+        return Err(BsnWriteBackError::Component(
+            component_path,
+            "only components that are their own props are supported",
+        ));
+    }
+
+    let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+        return Err(BsnWriteBackError::Component(
+            component_path,
+            "missing #[reflect(Component)]",
+        ));
+    };
+    let Some(reflected) = reflect_component.reflect(world.entity(entity)) else {
+        return Err(BsnWriteBackError::Component(
+            component_path,
+            "not present on the entity",
+        ));
+    };
+
+    let field = match (reflected.reflect_ref(), member) {
+        (ReflectRef::Struct(s), Member::Named(name)) => s.field(&name.to_string()),
+        (ReflectRef::TupleStruct(s), Member::Unnamed(index)) => s.field(index.index as usize),
+        _ => None,
+    };
+    let field_name = match member {
+        Member::Named(name) => name.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    };
+    let Some(field) = field else {
+        return Err(BsnWriteBackError::FieldNotFound(component_path, field_name));
+    };
+
+    let Some(value) = bsn_expr_from_reflect(field) else {
+        return Err(BsnWriteBackError::UnsupportedValue(
+            component_path,
+            field_name,
+        ));
+    };
+
+    Ok((component_path, value))
+}
+
+/// Formats a reflected value as a bsn expression, the inverse of parsing it for hot reload.
+pub fn bsn_expr_from_reflect(value: &dyn PartialReflect) -> Option<String> {
+    macro_rules! primitives {
+        ($($ty:ty),*) => {
+            $(if let Some(value) = value.try_downcast_ref::<$ty>() {
+                return Some(format!("{value:?}"));
+            })*
+        };
+    }
+    primitives!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, f32, f64, bool, String);
+
+    let path = value
+        .get_represented_type_info()?
+        .type_path_table()
+        .short_path();
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(s) => {
+            let fields = join_named_fields(
+                (0..s.field_len()).map(|i| (s.name_at(i).unwrap(), s.field_at(i).unwrap())),
+            )?;
+            Some(format!("{path} {{ {fields} }}"))
+        }
+        ReflectRef::TupleStruct(s) => {
+            let fields = join_fields(s.iter_fields())?;
+            Some(format!("{path}({fields})"))
+        }
+        ReflectRef::Enum(e) => {
+            let variant = e.variant_name();
+            match e.variant_type() {
+                VariantType::Unit => Some(format!("{path}::{variant}")),
+                VariantType::Tuple => {
+                    let fields = join_fields(e.iter_fields().map(|field| field.value()))?;
+                    Some(format!("{path}::{variant}({fields})"))
+                }
+                VariantType::Struct => {
+                    let fields = join_named_fields(
+                        e.iter_fields()
+                            .map(|field| (field.name().unwrap(), field.value())),
+                    )?;
+                    Some(format!("{path}::{variant} {{ {fields} }}"))
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+fn join_fields<'a>(values: impl Iterator<Item = &'a dyn PartialReflect>) -> Option<String> {
+    values
+        .map(bsn_expr_from_reflect)
+        .collect::<Option<Vec<_>>>()
+        .map(|values| values.join(", "))
+}
+
+fn join_named_fields<'a>(
+    values: impl Iterator<Item = (&'a str, &'a dyn PartialReflect)>,
+) -> Option<String> {
+    values
+        .map(|(name, value)| bsn_expr_from_reflect(value).map(|value| format!("{name}: {value}")))
+        .collect::<Option<Vec<_>>>()
+        .map(|values| values.join(", "))
+}
+
+/// Resolves the path of a loaded source file on disk, relative to the manifest directory the sources are
+/// registered from with [`BsnHotReloadAppExt::register_bsn_hot_reload_source`](super::BsnHotReloadAppExt::register_bsn_hot_reload_source).
+///
+/// `CARGO_MANIFEST_DIR` is only set when the app runs through cargo, without it relative paths are an error
+/// instead of being written relative to the working directory.
+fn source_file_path(
+    path: &str,
+    manifest_dir: Option<OsString>,
+) -> Result<PathBuf, BsnWriteBackError> {
+    if Path::new(path).is_absolute() {
+        return Ok(PathBuf::from(path));
+    }
+    let manifest_dir =
+        manifest_dir.ok_or_else(|| BsnWriteBackError::ManifestDirNotSet(path.to_string()))?;
+    Ok(Path::new(&manifest_dir).join(path))
+}
+
+/// Converts a 1-based line and 0-based char column to a byte offset in `content`.
+///
+/// Returns `None` if the line doesn't exist or the column is past its end.
+fn offset(content: &str, position: Position) -> Option<usize> {
+    let line_start = if position.line == 1 {
+        0
+    } else {
+        content
+            .match_indices('\n')
+            .nth(position.line - 2)
+            .map(|(i, _)| i + 1)?
+    };
+    let line = content[line_start..].split('\n').next()?;
+    let column = line
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([line.len()])
+        .nth(position.column)?;
+    Some(line_start + column)
+}
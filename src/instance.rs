@@ -0,0 +1,443 @@
+use alloc::{borrow::Cow, collections::VecDeque};
+
+use bevy::{
+    ecs::{component::ComponentId, system::EntityCommands},
+    prelude::{
+        BuildChildren, Children, Component, DespawnRecursiveExt, Entity, Parent, ReflectComponent,
+        World,
+    },
+    reflect::Reflect,
+    utils::HashMap,
+};
+
+use crate::{EntityCommandsSceneExt, ReflectConstruct, Scene};
+
+/// Identifies the node of a scene an entity was spawned from, by the path of child indices from the root.
+///
+/// Each index counts the children the scene spawned under the parent node, in the order they were spawned,
+/// so children that are not part of the scene don't shift them. Ids are kept for the lifetime of the instance,
+/// and children spawned by later constructions get the next free index under their parent.
+///
+/// The root of the scene has an empty path.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SceneNodeId(pub Vec<u32>);
+
+/// The entities spawned by a scene, kept on the root entity of the scene instance.
+///
+/// Inserted when a scene is constructed through [`ConstructContextSceneExt`](crate::ConstructContextSceneExt),
+/// which all the `construct_scene` and `spawn_scene` methods go through.
+#[derive(Component, Clone, Debug, Default)]
+pub struct SceneInstance {
+    /// The entities of the instance and the scene nodes they came from, starting with the root.
+    pub entities: Vec<(SceneNodeId, Entity)>,
+    /// The components the scene inserted on the root entity.
+    pub components: Vec<ComponentId>,
+}
+
+impl SceneInstance {
+    /// The root entity of the instance.
+    pub fn root(&self) -> Option<Entity> {
+        self.entities.first().map(|(_, entity)| *entity)
+    }
+
+    /// The entity spawned from the given scene node.
+    pub fn entity(&self, node: &SceneNodeId) -> Option<Entity> {
+        self.entities
+            .iter()
+            .find(|(id, _)| id == node)
+            .map(|(_, entity)| *entity)
+    }
+
+    /// The scene node the entity was spawned from.
+    pub fn node(&self, entity: Entity) -> Option<&SceneNodeId> {
+        self.entities
+            .iter()
+            .find(|(_, e)| *e == entity)
+            .map(|(id, _)| id)
+    }
+}
+
+/// Names the entity in the [`SceneRefs`] of its scene instance. Inserted by `#[ref(name)]` in `bsn!`.
+#[derive(Component, Reflect, Clone, Debug, Default, PartialEq, Eq)]
+#[reflect(Component, Construct)]
+pub struct SceneRef(pub Cow<'static, str>);
+
+impl SceneRef {
+    /// Creates a reference with the given name.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// The entities of a scene instance tagged with `#[ref(name)]` in `bsn!`, by name.
+///
+/// Inserted on the root entity along with the [`SceneInstance`].
+#[derive(Component, Clone, Debug, Default)]
+pub struct SceneRefs(pub HashMap<Cow<'static, str>, Entity>);
+
+impl SceneRefs {
+    /// The entity tagged with the given name.
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.0.get(name).copied()
+    }
+}
+
+/// Records the children a scene spawned, their descendants, and the components it inserted on `root`
+/// in the [`SceneInstance`] of `root`.
+///
+/// Each child comes with the parent it was spawned under, which is `root` or one of its recorded entities.
+/// Constructing another scene on the root adds to the existing instance.
+pub(crate) fn record_scene_instance(
+    world: &mut World,
+    root: Entity,
+    mut components: Vec<ComponentId>,
+    children: Vec<(Entity, Entity)>,
+) {
+    // The hierarchy of the root is not owned by the scene
+    let hierarchy = [
+        world.component_id::<Parent>(),
+        world.component_id::<Children>(),
+        world.component_id::<SceneInstance>(),
+        world.component_id::<SceneRefs>(),
+    ];
+    components.retain(|component| !hierarchy.contains(&Some(*component)));
+
+    let mut instance = world
+        .get::<SceneInstance>(root)
+        .cloned()
+        .unwrap_or_default();
+    if instance.entities.is_empty() {
+        instance.entities.push((SceneNodeId::default(), root));
+    }
+    for component in components {
+        if !instance.components.contains(&component) {
+            instance.components.push(component);
+        }
+    }
+
+    // The next free child index of each node
+    let mut next_indices = HashMap::<SceneNodeId, u32>::default();
+    for (SceneNodeId(path), _) in instance.entities.iter() {
+        if let Some((index, parent)) = path.split_last() {
+            let next = next_indices
+                .entry(SceneNodeId(parent.to_vec()))
+                .or_default();
+            *next = (*next).max(index + 1);
+        }
+    }
+
+    // Breadth first, so siblings are numbered in the order they were spawned.
+    // Entities that are already recorded are skipped, so other children added under them aren't adopted.
+    let mut queue = children
+        .into_iter()
+        .filter_map(|(parent, child)| Some((instance.node(parent)?.clone(), child)))
+        .collect::<VecDeque<_>>();
+    while let Some((parent, entity)) = queue.pop_front() {
+        if instance.node(entity).is_some() {
+            continue;
+        }
+        let next = next_indices.entry(parent.clone()).or_default();
+        let mut path = parent.0;
+        path.push(*next);
+        *next += 1;
+        let node = SceneNodeId(path);
+        instance.entities.push((node.clone(), entity));
+        queue.extend(
+            world
+                .get::<Children>(entity)
+                .into_iter()
+                .flatten()
+                .map(|child| (node.clone(), *child)),
+        );
+    }
+
+    let mut refs = world.get::<SceneRefs>(root).cloned().unwrap_or_default();
+    for (_, entity) in instance.entities.iter() {
+        if let Some(SceneRef(name)) = world.get::<SceneRef>(*entity) {
+            refs.0.insert(name.clone(), *entity);
+        }
+    }
+
+    let mut root = world.entity_mut(root);
+    root.insert(instance);
+    if !refs.0.is_empty() {
+        root.insert(refs);
+    }
+}
+
+/// Records the children spawned while reconstructing `id` in the [`SceneInstance`] it belongs to,
+/// each with the parent it was spawned under, and forgets the entities of the instance that were despawned.
+///
+/// Without an instance, `id` becomes the root of a new one.
+pub(crate) fn refresh_scene_instance(
+    world: &mut World,
+    id: Entity,
+    spawned: Vec<(Entity, Entity)>,
+) {
+    let Some(root) = scene_instance_root(world, id) else {
+        record_scene_instance(world, id, Vec::new(), spawned);
+        return;
+    };
+    let mut instance = world
+        .get::<SceneInstance>(root)
+        .cloned()
+        .unwrap_or_default();
+    instance
+        .entities
+        .retain(|(_, entity)| world.get_entity(*entity).is_ok());
+
+    let mut entity = world.entity_mut(root);
+    entity.insert(instance);
+    entity.remove::<SceneRefs>();
+    record_scene_instance(world, root, Vec::new(), spawned);
+}
+
+/// The root of the [`SceneInstance`] the entity was spawned by: the nearest of the entity and its ancestors
+/// whose instance records it.
+pub(crate) fn scene_instance_root(world: &World, entity: Entity) -> Option<Entity> {
+    let mut ancestor = Some(entity);
+    while let Some(id) = ancestor {
+        if world
+            .get::<SceneInstance>(id)
+            .is_some_and(|instance| instance.node(entity).is_some())
+        {
+            return Some(id);
+        }
+        ancestor = world.get::<Parent>(id).map(Parent::get);
+    }
+    None
+}
+
+/// The children of `parent` spawned by the [`SceneInstance`] it belongs to.
+pub(crate) fn scene_children(world: &World, parent: Entity) -> Vec<Entity> {
+    let Some(instance) =
+        scene_instance_root(world, parent).and_then(|root| world.get::<SceneInstance>(root))
+    else {
+        return Vec::new();
+    };
+    world
+        .get::<Children>(parent)
+        .into_iter()
+        .flatten()
+        .filter(|child| instance.node(**child).is_some())
+        .copied()
+        .collect()
+}
+
+/// Despawns the entities of the [`SceneInstance`] rooted at `root`, including the root.
+///
+/// Without an instance, the root is despawned recursively.
+pub fn despawn_scene_instance(world: &mut World, root: Entity) {
+    let entities = match world.get::<SceneInstance>(root) {
+        Some(instance) => instance.entities.clone(),
+        None => vec![(SceneNodeId::default(), root)],
+    };
+    for (_, entity) in entities {
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+/// Despawns the entities of the [`SceneInstance`] rooted at `root` and removes the components it inserted,
+/// keeping the root entity itself.
+pub fn clear_scene_instance(world: &mut World, root: Entity) {
+    let Some(instance) = world.entity_mut(root).take::<SceneInstance>() else {
+        return;
+    };
+    for (_, entity) in instance.entities.iter().skip(1) {
+        if let Ok(entity) = world.get_entity_mut(*entity) {
+            entity.despawn_recursive();
+        }
+    }
+    let mut entity = world.entity_mut(root);
+    for component in instance.components {
+        entity.remove_by_id(component);
+    }
+}
+
+/// Extension trait to manage [`SceneInstance`]s through [`EntityCommands`].
+pub trait SceneInstanceCommandsExt {
+    /// Despawns the scene instance rooted at the entity as a unit.
+    fn despawn_scene_instance(&mut self);
+
+    /// Replaces the scene instance rooted at the entity by a new scene, keeping the root entity.
+    fn replace_scene_instance(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
+
+    /// Despawns the scene instance rooted at the entity, and spawns the scene as a new entity in its place.
+    fn respawn_scene_instance(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_>;
+}
+
+impl<'w> SceneInstanceCommandsExt for EntityCommands<'w> {
+    fn despawn_scene_instance(&mut self) {
+        self.queue(|root: Entity, world: &mut World| despawn_scene_instance(world, root));
+    }
+
+    fn replace_scene_instance(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        self.queue(|root: Entity, world: &mut World| clear_scene_instance(world, root));
+        self.construct_scene(scene)
+    }
+
+    fn respawn_scene_instance(&mut self, scene: impl Scene + Send + 'static) -> EntityCommands<'_> {
+        let old = self.id();
+        let mut entity = self.commands_mut().spawn_empty();
+        entity.queue(move |new: Entity, world: &mut World| {
+            let parent = world.get::<Parent>(old).map(Parent::get);
+            let index = parent
+                .and_then(|parent| world.get::<Children>(parent))
+                .and_then(|children| children.iter().position(|child| *child == old));
+            despawn_scene_instance(world, old);
+            if let (Some(parent), Some(index)) = (parent, index) {
+                world.entity_mut(parent).insert_children(index, &[new]);
+            }
+        });
+        entity.construct_scene(scene);
+        entity
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{ChildBuild, Reflect};
+
+    use super::*;
+    use crate::{bsn, ConstructPatchExt, EntityWorldMutSceneExt, SpawnSceneExt, WorldSceneExt};
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    struct Label(u32);
+
+    fn list() -> impl Scene {
+        bsn! { Label(0u32) [ Label(1u32), Label(2u32) [ Label(3u32) ] ] }
+    }
+
+    #[test]
+    fn test_scene_refs() {
+        let mut world = World::new();
+        let root = world
+            .spawn_scene(bsn! {
+                #[ref(root)]
+                Label(0u32) [
+                    Label(1u32),
+                    #[ref(ok_button)]
+                    Label(2u32),
+                ]
+            })
+            .unwrap();
+
+        let refs = world.get::<SceneRefs>(root).unwrap();
+        assert_eq!(refs.get("root"), Some(root));
+        let ok_button = refs.get("ok_button").unwrap();
+        assert_eq!(world.get::<Label>(ok_button), Some(&Label(2)));
+    }
+
+    #[test]
+    fn test_scene_instance() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        world.commands().entity(parent).with_children(|parent| {
+            parent.spawn(Label(9));
+            parent.spawn_scene(list());
+        });
+        world.flush();
+
+        let root = world.get::<Children>(parent).unwrap()[1];
+        let instance = world.get::<SceneInstance>(root).unwrap();
+        assert_eq!(instance.root(), Some(root));
+        assert_eq!(instance.entities.len(), 4);
+        let leaf = instance.entity(&SceneNodeId(vec![1, 0])).unwrap();
+        assert_eq!(world.get::<Label>(leaf), Some(&Label(3)));
+
+        // Respawned in place
+        let mut commands = world.commands();
+        let respawned = commands
+            .entity(root)
+            .respawn_scene_instance(bsn! { Label(4u32) [ Label(5u32) ] })
+            .id();
+        world.flush();
+        assert_eq!(world.get::<Children>(parent).unwrap()[1], respawned);
+        assert!(world.get_entity(root).is_err());
+
+        // Replaced on the same root
+        world
+            .commands()
+            .entity(respawned)
+            .replace_scene_instance(bsn! { () [ Label(6u32), Label(7u32) ] });
+        world.flush();
+        assert!(world.get::<Label>(respawned).is_none());
+        assert_eq!(world.get::<Children>(respawned).unwrap().len(), 2);
+
+        world.commands().entity(respawned).despawn_scene_instance();
+        world.flush();
+        assert_eq!(world.query::<&Label>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn test_scene_node_ids() {
+        let mut world = World::new();
+        let root = world
+            .spawn_empty()
+            .with_children(|parent| {
+                parent.spawn(Label(9));
+            })
+            .id();
+
+        // Children that are not part of the scene don't count
+        world.entity_mut(root).construct_scene(list()).unwrap();
+        let instance = world.get::<SceneInstance>(root).unwrap();
+        let first = instance.entity(&SceneNodeId(vec![0])).unwrap();
+        assert_eq!(world.get::<Label>(first), Some(&Label(1)));
+        let leaf = instance.entity(&SceneNodeId(vec![1, 0])).unwrap();
+        assert_eq!(world.get::<Label>(leaf), Some(&Label(3)));
+
+        // Nor do siblings inserted later, and another scene on the root continues the numbering
+        let sibling = world.spawn(Label(7)).id();
+        world.entity_mut(root).insert_children(0, &[sibling]);
+        world
+            .entity_mut(root)
+            .construct_scene(bsn! { () [ Label(4u32) ] })
+            .unwrap();
+        let instance = world.get::<SceneInstance>(root).unwrap();
+        assert_eq!(instance.entity(&SceneNodeId(vec![0])), Some(first));
+        assert_eq!(instance.entity(&SceneNodeId(vec![1, 0])), Some(leaf));
+        let added = instance.entity(&SceneNodeId(vec![2])).unwrap();
+        assert_eq!(world.get::<Label>(added), Some(&Label(4)));
+        assert_eq!(instance.node(sibling), None);
+    }
+
+    #[test]
+    fn test_fragment_instances() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        world.commands().entity(parent).with_children(|parent| {
+            parent.spawn(Label(9));
+            parent
+                .spawn_scene(bsn! { <> [ Label(1u32) [ Label(2u32) ], #[ref(last)] Label(3u32) ] });
+            parent.spawn(Label(10));
+        });
+        world.flush();
+
+        // Spliced in place of the fragment, each child as the root of its own instance
+        let children = world.get::<Children>(parent).unwrap().to_vec();
+        let labels = children
+            .iter()
+            .map(|child| world.get::<Label>(*child).unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [9, 1, 3, 10]);
+
+        let instance = world.get::<SceneInstance>(children[1]).unwrap();
+        assert_eq!(instance.root(), Some(children[1]));
+        assert_eq!(instance.entities.len(), 2);
+        let leaf = instance.entity(&SceneNodeId(vec![0])).unwrap();
+        assert_eq!(world.get::<Label>(leaf), Some(&Label(2)));
+
+        let refs = world.get::<SceneRefs>(children[2]).unwrap();
+        assert_eq!(refs.get("last"), Some(children[2]));
+
+        world
+            .commands()
+            .entity(children[1])
+            .despawn_scene_instance();
+        world.flush();
+        assert_eq!(world.query::<&Label>().iter(&world).count(), 3);
+    }
+}
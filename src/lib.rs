@@ -1,9 +1,14 @@
 //! Naive, incomplete, and hacky implementation of the "Next generation scene proposal" for Bevy.
+//!
+//! Hot reloading of `bsn!` invocations is enabled by the default `hot_reload` feature.
+//! Disable it for release builds to make `bsn!` expand to a plain [`EntityPatch`].
 
 #![allow(internal_features)]
 #![cfg_attr(any(docsrs, docsrs_dep), feature(rustdoc_internals))]
 
 extern crate alloc;
+// Lets `#[derive(Construct)]` refer to this crate as `cant_wait_for_bsn` from within it.
+extern crate self as cant_wait_for_bsn;
 
 mod bsn_helpers;
 mod bsn_reflect;
@@ -11,10 +16,14 @@ mod construct;
 mod construct_impls;
 mod construct_reflect;
 mod dynamic;
+mod either;
 mod entity_patch;
 mod hot_patch;
+#[cfg(feature = "hot_reload")]
 mod hot_reload;
+mod instance;
 mod patch;
+mod reconstruct;
 
 use bevy::app::App;
 use bevy::app::Plugin;
@@ -25,24 +34,43 @@ pub use construct::*;
 pub use construct_impls::*;
 pub use construct_reflect::*;
 pub use dynamic::*;
+pub use either::*;
 pub use entity_patch::*;
 pub use hot_patch::*;
+#[cfg(feature = "hot_reload")]
 pub use hot_reload::*;
+pub use instance::*;
 pub use patch::*;
+pub use reconstruct::*;
 
-//pub use cant_wait_for_bsn_macros::bsn;
-pub use cant_wait_for_bsn_macros::bsn_hot as bsn; // TODO: Feature flag
+#[cfg(not(feature = "hot_reload"))]
+pub use cant_wait_for_bsn_macros::bsn;
+#[cfg(feature = "hot_reload")]
+pub use cant_wait_for_bsn_macros::bsn_hot as bsn;
 
 pub use cant_wait_for_bsn_macros::Construct;
 
 pub use cant_wait_for_bsn_parse as parse;
 
+/// Items used by the code generated by the macros.
+#[doc(hidden)]
+pub mod __macro_exports {
+    pub use crate::{
+        RegisterConstruct, RegisterConstructComponent, RegisterNothing, RegistrationProbe,
+    };
+    pub use inventory;
+}
+
 /// Registers all the necessary types for reflection-based dynamic scenes.
 pub struct CantWaitForBsnPlugin;
 
 impl Plugin for CantWaitForBsnPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<ConstructableTextFont>();
+        app.init_resource::<SceneErrorPolicy>()
+            .add_event::<SceneConstructFailed>()
+            .register_type::<SceneRef>()
+            .register_type::<SceneKey>();
+        register_derived_constructs(app);
         register_reflect_construct(app);
         register_reflect_from_bsn(app);
     }
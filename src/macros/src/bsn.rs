@@ -41,6 +41,23 @@ pub fn bsn_hot(item: TokenStream) -> TokenStream {
     out
 }
 
+/// Largest tuple implementing the scene and patch traits.
+const MAX_TUPLE_LEN: usize = 12;
+
+/// Builds a tuple of the items, nesting chunks of them with `nest` past [`MAX_TUPLE_LEN`].
+fn scene_tuple(
+    mut items: Vec<TokenStream>,
+    nest: impl Fn(TokenStream) -> TokenStream,
+) -> TokenStream {
+    while items.len() > MAX_TUPLE_LEN {
+        items = items
+            .chunks(MAX_TUPLE_LEN)
+            .map(|chunk| nest(quote! { (#(#chunk,)*) }))
+            .collect();
+    }
+    quote! { (#(#items,)*) }
+}
+
 trait ToTokensInternal {
     fn to_tokens(&self, tokens: &mut TokenStream);
 
@@ -75,17 +92,56 @@ impl ToTokensInternal for BsnEntity {
             "cant_wait_for_bsn",
             proc_macro2::Span::call_site(),
         ));
-        let patch = &self.patch.to_token_stream();
-        let inherits = self.inherits.iter().map(|i| i.to_token_stream());
-        let children = self.children.iter().map(|i| i.to_token_stream());
-        quote! {
+        let nested_scenes = |tuple| quote! { #cant_wait_for_bsn::NestedScenes(#tuple) };
+        let children = scene_tuple(
+            self.children.iter().map(|i| i.to_token_stream()).collect(),
+            nested_scenes,
+        );
+        if self.fragment {
+            quote! {
+                #cant_wait_for_bsn::Fragment {
+                    children: #children,
+                }
+            }
+            .to_tokens(tokens);
+            return;
+        }
+
+        let mut patch = self.patch.to_token_stream();
+        if let Some(ref_name) = &self.ref_name {
+            let ref_name = ref_name.to_string();
+            patch = quote! {
+                (
+                    #patch,
+                    #cant_wait_for_bsn::ConstructPatch::new_inferred(
+                        move |props: &mut #cant_wait_for_bsn::SceneRef| {
+                            *props = #cant_wait_for_bsn::SceneRef::new(#ref_name);
+                        }
+                    ),
+                )
+            };
+        }
+        let inherits = scene_tuple(
+            self.inherits.iter().map(|i| i.to_token_stream()).collect(),
+            nested_scenes,
+        );
+        let entity = quote! {
             #cant_wait_for_bsn::EntityPatch {
-                inherit: (#(#inherits,)*),
+                inherit: #inherits,
                 patch: #patch,
-                children: (#(#children,)*),
+                children: #children,
             }
+        };
+        match &self.key {
+            Some(key) => quote! {
+                #cant_wait_for_bsn::Keyed {
+                    key: #cant_wait_for_bsn::SceneKey::new(&(#key)),
+                    scene: #entity,
+                }
+            }
+            .to_tokens(tokens),
+            None => entity.to_tokens(tokens),
         }
-        .to_tokens(tokens);
     }
 }
 
@@ -101,6 +157,7 @@ impl ToTokensInternal for BsnChildren {
                 #cant_wait_for_bsn::SceneIter::new(#expr)
             }
             .to_tokens(tokens),
+            BsnChildren::Conditional(conditional) => conditional.to_tokens(tokens),
         }
     }
 }
@@ -113,25 +170,41 @@ impl ToTokensInternal for BsnPatch {
         ));
         match self {
             BsnPatch::Patch(path, fields) => {
-                let assignments = fields.iter().map(|(member, prop)| {
-                    let member = member.to_token_stream();
-                    let prop = prop.to_token_stream();
+                let assignments = prop_assignments(fields);
+                let field_names = fields.iter().map(|(member, _)| member.to_string());
+                let appending = prop_resets(path, fields).map(|resets| {
                     quote! {
-                        props.#member = #prop;
+                        .appending(|props| #resets)
                     }
                 });
                 quote! {
                     #path::patch(move |props| {
                         #(#assignments)*
                     })
+                    .with_fields(&[#(#field_names),*])
+                    #appending
+                }
+            }
+            BsnPatch::Replace(path, fields) => {
+                let assignments = prop_assignments(fields);
+                quote! {
+                    #path::patch(move |props| {
+                        #(#assignments)*
+                    })
+                    .replacing()
                 }
             }
+            BsnPatch::Tuple(tuple) if tuple.len() > MAX_TUPLE_LEN => scene_tuple(
+                tuple.iter().map(|patch| patch.to_token_stream()).collect(),
+                |tuple| tuple,
+            ),
             BsnPatch::Tuple(tuple) => {
                 let tuple = tuple.to_token_stream();
                 quote! {
                     (#tuple)
                 }
             }
+            BsnPatch::Conditional(conditional) => conditional.to_token_stream(),
             BsnPatch::Expr(expr) => quote! {
                 #cant_wait_for_bsn::ConstructPatch::new_inferred(move |props| {
                     *props = #expr;
@@ -142,6 +215,86 @@ impl ToTokensInternal for BsnPatch {
     }
 }
 
+impl<T: ToTokensInternal> ToTokensInternal for BsnConditional<T> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let cant_wait_for_bsn = syn::Path::from(Ident::new(
+            "cant_wait_for_bsn",
+            proc_macro2::Span::call_site(),
+        ));
+        // Each branch is wrapped in a `Conditional` with its index, so hot reload can tell which one was taken
+        let conditional = |index: usize, body: TokenStream| {
+            quote! {
+                #cant_wait_for_bsn::Conditional {
+                    branch: #index,
+                    value: #body,
+                }
+            }
+        };
+        match self {
+            BsnConditional::If(branches) => {
+                // Without a final `else`, the entry is optional
+                let has_else = branches
+                    .last()
+                    .is_some_and(|(condition, _)| condition.is_none());
+                let branches = branches
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (condition, body))| {
+                        let body = either_branch(index, branches.len(), body.to_token_stream());
+                        let body = match has_else {
+                            true => body,
+                            false => quote! { ::core::option::Option::Some(#body) },
+                        };
+                        let body = conditional(index, body);
+                        match condition {
+                            Some(condition) => quote! { if #condition { #body } },
+                            None => quote! { { #body } },
+                        }
+                    });
+                let none = (!has_else).then(|| {
+                    let none = conditional(branches.len(), quote! { ::core::option::Option::None });
+                    quote! { { #none } }
+                });
+                let branches = branches.chain(none);
+                quote! {
+                    #(#branches)else*
+                }
+            }
+            BsnConditional::Match(expr, arms) => {
+                let arms = arms.iter().enumerate().map(|(index, arm)| {
+                    let BsnArm { pat, guard, body } = arm;
+                    let guard = guard.as_ref().map(|guard| quote! { if #guard });
+                    let body = either_branch(index, arms.len(), body.to_token_stream());
+                    let body = conditional(index, body);
+                    quote! { #pat #guard => #body, }
+                });
+                quote! {
+                    match #expr {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        .to_tokens(tokens);
+    }
+}
+
+/// Wraps the branch at `index` in nested `Either`s, so `count` branches of different types unify.
+fn either_branch(index: usize, count: usize, body: TokenStream) -> TokenStream {
+    let cant_wait_for_bsn = syn::Path::from(Ident::new(
+        "cant_wait_for_bsn",
+        proc_macro2::Span::call_site(),
+    ));
+    if count == 1 {
+        body
+    } else if index == 0 {
+        quote! { #cant_wait_for_bsn::Either::Left(#body) }
+    } else {
+        let body = either_branch(index - 1, count - 1, body);
+        quote! { #cant_wait_for_bsn::Either::Right(#body) }
+    }
+}
+
 impl ToTokensInternal for BsnProp {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let cant_wait_for_bsn = syn::Path::from(Ident::new(
@@ -155,11 +308,87 @@ impl ToTokensInternal for BsnProp {
             BsnProp::Prop(expr) => quote! {
                 #cant_wait_for_bsn::ConstructProp::Prop((#expr).into())
             },
+            // Nested props and appends patch the field in place, see `prop_assignments`.
+            BsnProp::Props(BsnProps { path, .. }) => {
+                syn::Error::new_spanned(path, "nested props can only patch a field")
+                    .to_compile_error()
+            }
+            BsnProp::Append(expr) => {
+                syn::Error::new_spanned(expr, "`+=` can only append to a field").to_compile_error()
+            }
         }
         .to_tokens(tokens);
     }
 }
 
+fn prop_assignments(fields: &[(BsnMemberPath, BsnProp)]) -> impl Iterator<Item = TokenStream> + '_ {
+    let cant_wait_for_bsn = syn::Path::from(Ident::new(
+        "cant_wait_for_bsn",
+        proc_macro2::Span::call_site(),
+    ));
+    fields
+        .iter()
+        .map(move |(BsnMemberPath(members), prop)| match prop {
+            // Patches the existing props of the field, keeping the inner fields set by earlier patches
+            BsnProp::Props(BsnProps { path, fields, .. }) => {
+                let assignments = prop_assignments(fields);
+                quote! {
+                    {
+                        let props: &mut <#path as #cant_wait_for_bsn::Construct>::Props =
+                            #cant_wait_for_bsn::ConstructProp::props_mut(&mut props #(.#members)*);
+                        #(#assignments)*
+                    }
+                }
+            }
+            BsnProp::Append(expr) => quote! {
+                ::core::iter::Extend::extend(
+                    &mut props #(.#members)*,
+                    ::core::iter::IntoIterator::into_iter(#expr).map(::core::convert::Into::into),
+                );
+            },
+            prop => {
+                let prop = prop.to_token_stream();
+                quote! {
+                    props #(.#members)* = #prop;
+                }
+            }
+        })
+}
+
+/// Resets the appended fields of the props of `path` to their default, or `None` if nothing is appended.
+fn prop_resets(path: &syn::Path, fields: &[(BsnMemberPath, BsnProp)]) -> Option<TokenStream> {
+    let cant_wait_for_bsn = syn::Path::from(Ident::new(
+        "cant_wait_for_bsn",
+        proc_macro2::Span::call_site(),
+    ));
+    let resets = fields
+        .iter()
+        .filter_map(|(BsnMemberPath(members), prop)| match prop {
+            BsnProp::Props(BsnProps { path, fields, .. }) => {
+                let resets = prop_resets(path, fields)?;
+                Some(quote! {
+                    {
+                        let props = #cant_wait_for_bsn::ConstructProp::<#path>::props_mut(&mut props #(.#members)*);
+                        #resets
+                    }
+                })
+            }
+            BsnProp::Append(_) => Some(quote! {
+                props #(.#members)* = ::core::clone::Clone::clone(&baseline #(.#members)*);
+            }),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    (!resets.is_empty()).then(|| {
+        quote! {
+            {
+                let baseline = <<#path as #cant_wait_for_bsn::Construct>::Props as ::core::default::Default>::default();
+                #(#resets)*
+            }
+        }
+    })
+}
+
 impl ToTokensInternal for BsnInherit {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let BsnInherit(path, params) = &self;
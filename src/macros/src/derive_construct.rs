@@ -2,152 +2,886 @@ use bevy_macro_utils::{
     fq_std::{FQClone, FQDefault, FQResult},
     BevyManifest,
 };
-use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse2, parse_quote, Data, DataStruct, DeriveInput, Field, Fields, Generics, Ident, Path,
-    Visibility,
+    parse2, parse_quote, Attribute, Data, DataEnum, DataStruct, DeriveInput, Expr, Field, Fields,
+    GenericArgument, GenericParam, Generics, Ident, Path, PathArguments, Type, Visibility,
+    WherePredicate,
 };
 
 pub fn derive_construct(item: TokenStream) -> TokenStream {
     match parse2::<DeriveInput>(item) {
-        Ok(s) => impl_construct(s),
+        Ok(s) => impl_construct(s).unwrap_or_else(|e| e.to_compile_error()),
         Err(e) => e.to_compile_error(),
     }
 }
 
-fn impl_construct(input: DeriveInput) -> TokenStream {
+fn impl_construct(input: DeriveInput) -> syn::Result<TokenStream> {
     let DeriveInput {
+        attrs,
         vis,
         ident,
         generics,
         data,
-        ..
     } = input;
 
+    let type_attrs = TypeAttrs::parse(&attrs)?;
+
     match data {
-        Data::Struct(ds) => impl_for_struct(vis, ident, generics, ds),
-        Data::Enum(_) => todo!("enums are not supported"),
-        Data::Union(_) => todo!("unions are not supported"),
+        Data::Struct(ds) => impl_for_struct(vis, ident, generics, ds, type_attrs),
+        Data::Enum(de) => {
+            if let Some(patch) = type_attrs.patch {
+                return Err(syn::Error::new(
+                    patch.span(),
+                    "`#[construct(patch)]` is only supported on structs with named fields",
+                ));
+            }
+            impl_for_enum(vis, ident, generics, de)
+        }
+        Data::Union(du) => Err(syn::Error::new(
+            du.union_token.span,
+            "`#[derive(Construct)]` does not support unions",
+        )),
     }
 }
 
-fn impl_for_struct(
-    vis: Visibility,
-    ident: Ident,
-    _generics: Generics, // TODO
-    data_struct: DataStruct,
-) -> TokenStream {
-    let props_ident = Ident::new(format!("{}{}", ident, "Props").as_str(), ident.span());
+/// Paths used by the generated code.
+struct Paths {
+    bevy_reflect: Path,
+    cant_wait_for_bsn: Path,
+}
 
-    let bevy_manifest = BevyManifest::default();
-    let bevy_reflect = bevy_manifest.get_path("bevy_reflect");
+impl Paths {
+    fn new() -> Self {
+        let bevy_manifest = BevyManifest::default();
+        Self {
+            bevy_reflect: bevy_manifest.get_path("bevy_reflect"),
+            cant_wait_for_bsn: Path::from(Ident::new("cant_wait_for_bsn", Span::call_site())),
+        }
+    }
+}
 
-    let cant_wait_for_bsn = Path::from(Ident::new("cant_wait_for_bsn", Span::call_site()));
+/// Options of the type, given by `#[construct(..)]`.
+#[derive(Default)]
+struct TypeAttrs {
+    /// `patch`: Generate a `{Name}Patch` type with typed patch helpers for each prop.
+    patch: Option<Ident>,
+}
 
-    let fields: Vec<(&Field, bool)> = data_struct
-        .fields
-        .iter()
-        .map(|field| {
-            let is_construct = field.attrs.iter().any(|attr| {
-                attr.meta
-                    .path()
-                    .get_ident()
-                    .map(|ident| ident.to_string())
-                    .unwrap_or_default()
-                    == "construct"
-            });
-            (field, is_construct)
-        })
-        .collect();
+impl TypeAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut type_attrs = TypeAttrs::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("construct"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("patch") {
+                    type_attrs.patch = meta.path.get_ident().cloned();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported construct attribute, expected `patch`"))
+                }
+            })?;
+        }
+        Ok(type_attrs)
+    }
+}
+
+/// Options of a field, given by `#[construct]` or `#[construct(..)]`.
+#[derive(Default)]
+struct FieldAttrs {
+    /// Whether the field has a bare `#[construct]` attribute, making its prop a `ConstructProp`.
+    construct: bool,
+    /// `default = expr`: Default value of the prop, or of the field if skipped.
+    default: Option<Expr>,
+    /// `skip`: The field is not part of the props, and computed at construct time.
+    skip: bool,
+    /// `with = path`: Function constructing the field from the context and the prop.
+    with: Option<Path>,
+    /// `props = Type`: Props type of the field, constructed into the field type.
+    props: Option<Type>,
+    /// `asset` or `entity`: The field is a `Handle<T>` or `Entity`, constructed through its wrapper type.
+    wrapper: Option<Wrapper>,
+}
 
-    let struct_fields = fields.iter().map(|(field, is_construct)| {
-        let mut field = Field::clone(field);
-        if *is_construct {
-            let ty = field.ty;
-            field.ty = parse_quote! { #cant_wait_for_bsn::ConstructProp<#ty> };
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field_attrs = FieldAttrs::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("construct"))
+        {
+            if attr.meta.require_path_only().is_ok() {
+                field_attrs.construct = true;
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    field_attrs.default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    field_attrs.skip = true;
+                } else if meta.path.is_ident("with") {
+                    field_attrs.with = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("props") {
+                    field_attrs.props = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("asset") {
+                    field_attrs.wrapper = Some(Wrapper::Handle);
+                } else if meta.path.is_ident("entity") {
+                    field_attrs.wrapper = Some(Wrapper::Entity);
+                } else {
+                    return Err(meta.error(
+                        "unsupported construct attribute, expected `default`, `skip`, `with`, `props`, `asset` or `entity`",
+                    ));
+                }
+                Ok(())
+            })?;
+            if field_attrs.skip && field_attrs.props.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`skip` and `props` can't be combined",
+                ));
+            }
+            if field_attrs.wrapper.is_some() && (field_attrs.skip || field_attrs.props.is_some()) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`asset` and `entity` can't be combined with `skip` or `props`",
+                ));
+            }
         }
-        field.attrs.clear();
-        field
-    });
+        Ok(field_attrs)
+    }
+}
 
-    let default_fields = fields.iter().map(|(field, is_construct)| {
-        let Field {
-            ident, colon_token, ..
-        } = field;
-        if *is_construct {
-            quote! { #ident #colon_token #cant_wait_for_bsn::ConstructProp::Prop(#FQDefault::default()) }
-        } else {
-            quote! { #ident #colon_token #FQDefault::default() }
+/// Bevy field types that are constructed through a wrapper type, so they can be given as `@` props.
+///
+/// Opted into with `#[construct(asset)]` and `#[construct(entity)]`, as the derive can't resolve the field type.
+#[derive(Clone, Copy, PartialEq)]
+enum Wrapper {
+    /// `Handle<T>`, constructed as `ConstructHandle<T>` from an asset path.
+    Handle,
+    /// `Entity`, constructed as `ConstructEntity` from an `EntityPath`.
+    Entity,
+}
+
+impl Wrapper {
+    /// The type wrapping the field type.
+    fn wrapping_type(self, ty: &Type, paths: &Paths) -> syn::Result<Type> {
+        let cant_wait_for_bsn = &paths.cant_wait_for_bsn;
+        match self {
+            Wrapper::Handle => {
+                let asset = match ty {
+                    Type::Path(type_path) => type_path.path.segments.last().and_then(|segment| {
+                        match &segment.arguments {
+                            PathArguments::AngleBracketed(arguments)
+                                if arguments.args.len() == 1 =>
+                            {
+                                match &arguments.args[0] {
+                                    GenericArgument::Type(asset) => Some(asset),
+                                    _ => None,
+                                }
+                            }
+                            _ => None,
+                        }
+                    }),
+                    _ => None,
+                };
+                let Some(asset) = asset else {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "`#[construct(asset)]` expects a `Handle<T>` field",
+                    ));
+                };
+                Ok(parse_quote! { #cant_wait_for_bsn::ConstructHandle<#asset> })
+            }
+            Wrapper::Entity => Ok(parse_quote! { #cant_wait_for_bsn::ConstructEntity }),
         }
-    });
+    }
+}
+
+/// A field of the constructed type, and how it maps to its props.
+struct ConstructField<'a> {
+    field: &'a Field,
+    /// Binding of the field when destructuring the props.
+    binding: Ident,
+    /// Name or index of the field, given as context to construction errors.
+    name: String,
+    attrs: FieldAttrs,
+    /// The type constructed from the `ConstructProp` of the field, if its prop is one.
+    construct_type: Option<Type>,
+    /// Set if `construct_type` wraps the field type.
+    wrapper: Option<Wrapper>,
+}
 
-    let construct_fields = fields.iter().map(|(field, is_construct)| {
-        let Field {
-            ident, colon_token, ..
-        } = field;
-        if *is_construct {
-            quote! { #ident #colon_token match props.#ident {
-                #cant_wait_for_bsn::ConstructProp::Prop(p) => {
-                    #cant_wait_for_bsn::Construct::construct(context, p)?
-                },
-                #cant_wait_for_bsn::ConstructProp::Value(v) => v,
-            } }
+impl<'a> ConstructField<'a> {
+    fn new(field: &'a Field, index: usize, paths: &Paths) -> syn::Result<Self> {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let wrapper = attrs.wrapper;
+        let construct_type = match wrapper {
+            Some(wrapper) => Some(wrapper.wrapping_type(&field.ty, paths)?),
+            None => attrs.construct.then(|| field.ty.clone()),
+        };
+        Ok(Self {
+            field,
+            binding: format_ident!("prop_{}", index),
+            name: field
+                .ident
+                .as_ref()
+                .map_or_else(|| index.to_string(), ToString::to_string),
+            attrs,
+            construct_type,
+            wrapper,
+        })
+    }
+
+    /// The type of the field in the props, or `None` if it's skipped.
+    fn props_type(&self, paths: &Paths) -> Option<Type> {
+        let cant_wait_for_bsn = &paths.cant_wait_for_bsn;
+        if self.attrs.skip {
+            None
+        } else if let Some(props) = &self.attrs.props {
+            Some(props.clone())
+        } else if let Some(construct_type) = &self.construct_type {
+            Some(parse_quote! { #cant_wait_for_bsn::ConstructProp<#construct_type> })
         } else {
-            quote! { #ident #colon_token props.#ident }
+            Some(self.field.ty.clone())
         }
-    });
+    }
 
-    match data_struct.fields {
-        Fields::Unit => quote! {
-            #[allow(missing_docs)]
-            #[derive(#FQClone, #bevy_reflect::Reflect, #FQDefault)]
-            #vis struct #props_ident;
+    /// The field as declared in the props type, or `None` if it's skipped.
+    fn props_field(&self, paths: &Paths) -> Option<Field> {
+        let mut field = self.field.clone();
+        field.ty = self.props_type(paths)?;
+        // Keep `#[reflect(..)]` attributes, e.g. to ignore fields that aren't reflectable.
+        field.attrs.retain(|attr| attr.path().is_ident("reflect"));
+        Some(field)
+    }
 
-            impl #cant_wait_for_bsn::Construct for #ident {
-                type Props = #props_ident;
-                fn construct(context: &mut #cant_wait_for_bsn::ConstructContext, props: Self::Props) -> #FQResult<Self, #cant_wait_for_bsn::ConstructError> {
-                    Ok(Self)
-                }
+    /// The default value of the field in the props type.
+    fn default_value(&self, paths: &Paths) -> TokenStream {
+        let cant_wait_for_bsn = &paths.cant_wait_for_bsn;
+        let ty = &self.field.ty;
+        match (&self.attrs.default, &self.construct_type, self.wrapper) {
+            (Some(default), Some(_), _) => {
+                quote! { #cant_wait_for_bsn::ConstructProp::Prop(#default) }
             }
-        },
-        Fields::Named(_) => quote! {
-            #[allow(missing_docs)]
-            #[derive(#FQClone, #bevy_reflect::Reflect)]
-            #vis struct #props_ident {
-                #(#struct_fields),*
+            (Some(default), None, _) => default.to_token_stream(),
+            // Default handles don't point to an asset path, so they're given as values.
+            (None, Some(_), Some(Wrapper::Handle)) => quote! {
+                #cant_wait_for_bsn::ConstructProp::Value(::core::convert::From::from(<#ty as #FQDefault>::default()))
+            },
+            (None, Some(_), _) => {
+                quote! { #cant_wait_for_bsn::ConstructProp::Prop(#FQDefault::default()) }
             }
+            (None, None, _) => quote! { #FQDefault::default() },
+        }
+    }
 
-            impl #FQDefault for #props_ident {
-                fn default() -> Self {
-                    Self { #(#default_fields),* }
+    /// Constructs the field value, from its prop bound to [`ConstructField::binding`] unless skipped.
+    fn construct_value(&self, paths: &Paths) -> TokenStream {
+        let cant_wait_for_bsn = &paths.cant_wait_for_bsn;
+        let ty = &self.field.ty;
+        let prop = &self.binding;
+        let name = &self.name;
+        // Propagates construction errors of the field, with the field as context
+        let try_field = quote! {
+            .map_err(|error| #cant_wait_for_bsn::ConstructError::with_context(
+                error,
+                #cant_wait_for_bsn::ConstructErrorContext::Field(::core::convert::Into::into(#name)),
+            ))?
+        };
+        match &self.attrs {
+            FieldAttrs {
+                skip: true,
+                with: Some(with),
+                ..
+            } => quote! { #with(context)#try_field },
+            FieldAttrs {
+                skip: true,
+                default: Some(default),
+                ..
+            } => default.to_token_stream(),
+            FieldAttrs { skip: true, .. } => quote! { #FQDefault::default() },
+            FieldAttrs {
+                with: Some(with), ..
+            } => quote! { #with(context, #prop)#try_field },
+            FieldAttrs { props: Some(_), .. } => quote! {
+                <#ty as #cant_wait_for_bsn::Construct>::construct(context, ::core::convert::Into::into(#prop))#try_field
+            },
+            _ if self.construct_type.is_some() => {
+                let value = quote! { match #prop {
+                    #cant_wait_for_bsn::ConstructProp::Prop(p) => {
+                        #cant_wait_for_bsn::Construct::construct(context, p)#try_field
+                    },
+                    #cant_wait_for_bsn::ConstructProp::Value(v) => v,
+                } };
+                match self.wrapper {
+                    Some(_) => quote! { ::core::convert::Into::into(#value) },
+                    None => value,
                 }
             }
+            _ => prop.to_token_stream(),
+        }
+    }
+}
 
-            impl #cant_wait_for_bsn::Construct for #ident {
-                type Props = #props_ident;
-                fn construct(context: &mut #cant_wait_for_bsn::ConstructContext, props: Self::Props) -> #FQResult<Self, #cant_wait_for_bsn::ConstructError> {
-                    Ok(Self { #(#construct_fields),* })
-                }
+/// The fields of a struct or enum variant, and how they map to its props.
+struct ConstructFields<'a> {
+    fields: &'a Fields,
+    construct_fields: Vec<ConstructField<'a>>,
+}
+
+impl<'a> ConstructFields<'a> {
+    fn new(fields: &'a Fields, paths: &Paths) -> syn::Result<Self> {
+        let construct_fields = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| ConstructField::new(field, index, paths))
+            .collect::<syn::Result<_>>()?;
+        Ok(Self {
+            fields,
+            construct_fields,
+        })
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &ConstructField<'a>> {
+        self.construct_fields.iter()
+    }
+
+    /// The fields that are part of the props.
+    fn props_fields(&self) -> impl Iterator<Item = &ConstructField<'a>> {
+        self.iter().filter(|field| !field.attrs.skip)
+    }
+
+    /// Declaration of the props fields: `{ a: A, .. }`, `(A, ..)` or nothing.
+    ///
+    /// `marker` is added as an extra field, if given.
+    fn props_declaration(&self, paths: &Paths, marker: Option<&TokenStream>) -> TokenStream {
+        let props_fields = self
+            .props_fields()
+            .filter_map(|field| field.props_field(paths));
+        match self.fields {
+            Fields::Named(_) => {
+                let marker = marker.map(|marker| quote! { #[reflect(ignore)] _marker: #marker });
+                quote! { { #(#props_fields,)* #marker } }
             }
-        },
-        Fields::Unnamed(_) => quote! {
-            #[allow(missing_docs)]
-            #[derive(#FQClone, #bevy_reflect::Reflect)]
-            #vis struct #props_ident ( #(#struct_fields),* );
+            Fields::Unnamed(_) => {
+                let marker = marker.map(|marker| quote! { #[reflect(ignore)] #marker });
+                quote! { ( #(#props_fields,)* #marker ) }
+            }
+            Fields::Unit => match marker {
+                Some(marker) => quote! { ( #[reflect(ignore)] #marker ) },
+                None => quote! {},
+            },
+        }
+    }
 
-            impl #FQDefault for #props_ident {
-                fn default() -> Self {
-                    Self( #(#default_fields),* )
+    /// The default props: `{ a: .., .. }`, `(.., ..)` or nothing.
+    fn props_default(&self, paths: &Paths, marker: bool) -> TokenStream {
+        self.props_values(
+            self.props_fields().map(|field| field.default_value(paths)),
+            marker,
+        )
+    }
+
+    /// Clones the props bound by [`ConstructFields::props_pattern`]: `{ a: .., .. }`, `(.., ..)` or nothing.
+    fn props_clone(&self, marker: bool) -> TokenStream {
+        self.props_values(
+            self.props_fields().map(|field| {
+                let binding = &field.binding;
+                quote! { #FQClone::clone(#binding) }
+            }),
+            marker,
+        )
+    }
+
+    /// Props built from a value per props field: `{ a: .., .. }`, `(.., ..)` or nothing.
+    fn props_values(&self, values: impl Iterator<Item = TokenStream>, marker: bool) -> TokenStream {
+        let marker = marker.then(|| quote! { ::core::marker::PhantomData });
+        match self.fields {
+            Fields::Named(_) => {
+                let idents = self.props_fields().map(|field| &field.field.ident);
+                let marker = marker.map(|marker| quote! { _marker: #marker });
+                quote! { { #(#idents: #values,)* #marker } }
+            }
+            Fields::Unnamed(_) => quote! { ( #(#values,)* #marker ) },
+            Fields::Unit => match marker {
+                Some(marker) => quote! { ( #marker ) },
+                None => quote! {},
+            },
+        }
+    }
+
+    /// Pattern binding the props fields: `{ a: prop_0, .. }`, `(prop_0, ..)` or nothing.
+    fn props_pattern(&self) -> TokenStream {
+        let bindings = self.props_fields().map(|field| &field.binding);
+        match self.fields {
+            Fields::Named(_) => {
+                let idents = self.props_fields().map(|field| &field.field.ident);
+                quote! { { #(#idents: #bindings,)* .. } }
+            }
+            Fields::Unnamed(_) => quote! { ( #(#bindings,)* .. ) },
+            Fields::Unit => quote! { { .. } },
+        }
+    }
+
+    /// Constructs all fields from the bound props: `{ a: .., .. }`, `(.., ..)` or nothing.
+    fn construct_body(&self, paths: &Paths) -> TokenStream {
+        let values = self.iter().map(|field| field.construct_value(paths));
+        match self.fields {
+            Fields::Named(_) => {
+                let idents = self.iter().map(|field| &field.field.ident);
+                quote! { { #(#idents: #values),* } }
+            }
+            Fields::Unnamed(_) => quote! { ( #(#values),* ) },
+            Fields::Unit => quote! {},
+        }
+    }
+}
+
+/// Generics of the constructed type, as carried through to the props type.
+struct PropsGenerics<'a> {
+    generics: &'a Generics,
+    /// Predicates the props type needs in addition to those of the constructed type.
+    predicates: Vec<WherePredicate>,
+    /// Predicates the `Default` impl of the props type needs.
+    default_predicates: Vec<WherePredicate>,
+    /// Predicates the `Clone` impl of the props type needs.
+    clone_predicates: Vec<WherePredicate>,
+    /// Predicates the `Construct` impl needs for computing skipped fields.
+    construct_predicates: Vec<WherePredicate>,
+    /// Type and lifetime parameters that no props field uses.
+    unused: Vec<TokenStream>,
+}
+
+impl<'a> PropsGenerics<'a> {
+    fn new<'f, 'g: 'f>(
+        generics: &'a Generics,
+        fields: impl IntoIterator<Item = &'f ConstructField<'g>>,
+        paths: &Paths,
+    ) -> Self {
+        let cant_wait_for_bsn = &paths.cant_wait_for_bsn;
+
+        let params = generics
+            .type_params()
+            .map(|param| param.ident.to_string())
+            .chain(
+                generics
+                    .lifetimes()
+                    .map(|param| param.lifetime.ident.to_string()),
+            )
+            .collect::<HashSet<_>>();
+        let uses_params = |ty: &Type, idents: &mut HashSet<String>| {
+            let mut ty_idents = HashSet::new();
+            collect_idents(ty.to_token_stream(), &mut ty_idents);
+            let uses_params = !ty_idents.is_disjoint(&params);
+            idents.extend(ty_idents);
+            uses_params
+        };
+
+        let mut used = HashSet::new();
+        let mut predicates = Vec::new();
+        let mut default_predicates = Vec::new();
+        let mut clone_predicates = Vec::new();
+        let mut construct_predicates = Vec::new();
+        for field in fields {
+            let ty = &field.field.ty;
+            let attrs = &field.attrs;
+            let Some(props_type) = field.props_type(paths) else {
+                if attrs.with.is_none()
+                    && attrs.default.is_none()
+                    && uses_params(ty, &mut HashSet::new())
+                {
+                    construct_predicates.push(parse_quote! { #ty: #FQDefault });
                 }
+                continue;
+            };
+            if !uses_params(&props_type, &mut used) {
+                continue;
             }
+            clone_predicates.push(parse_quote! { #props_type: #FQClone });
+            if attrs.with.is_none() {
+                if let Some(construct_type) = &field.construct_type {
+                    predicates
+                        .push(parse_quote! { #construct_type: #cant_wait_for_bsn::Construct });
+                } else if attrs.props.is_some() {
+                    predicates.push(parse_quote! { #ty: #cant_wait_for_bsn::Construct });
+                }
+            }
+            // Constructed props default to `ConstructProp::Prop(T::Props::default())`, which is always available.
+            if attrs.default.is_none() && field.construct_type.is_none() {
+                default_predicates.push(parse_quote! { #props_type: #FQDefault });
+            }
+        }
 
-            impl #cant_wait_for_bsn::Construct for #ident {
-                type Props = #props_ident;
-                fn construct(context: &mut #cant_wait_for_bsn::ConstructContext, props: Self::Props) -> #FQResult<Self, #cant_wait_for_bsn::ConstructError> {
-                    Ok(Self ( #(#construct_fields),* ))
+        let unused = generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(param) if !used.contains(&param.ident.to_string()) => {
+                    let ident = &param.ident;
+                    Some(quote! { #ident })
                 }
+                GenericParam::Lifetime(param)
+                    if !used.contains(&param.lifetime.ident.to_string()) =>
+                {
+                    let lifetime = &param.lifetime;
+                    Some(quote! { &#lifetime () })
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            generics,
+            predicates,
+            default_predicates,
+            clone_predicates,
+            construct_predicates,
+            unused,
+        }
+    }
+
+    /// The where clause of the constructed type, extended with the props predicates and `extra`.
+    fn where_clause<'p>(
+        &'p self,
+        extra: impl IntoIterator<Item = &'p WherePredicate>,
+    ) -> TokenStream {
+        let predicates = self
+            .generics
+            .where_clause
+            .iter()
+            .flat_map(|where_clause| where_clause.predicates.iter())
+            .chain(self.predicates.iter())
+            .chain(extra)
+            .collect::<Vec<_>>();
+        if predicates.is_empty() {
+            quote! {}
+        } else {
+            quote! { where #(#predicates),* }
+        }
+    }
+
+    /// The type of a marker field for the parameters no props field uses, if any.
+    fn marker(&self) -> Option<TokenStream> {
+        if self.unused.is_empty() {
+            return None;
+        }
+        let unused = &self.unused;
+        Some(quote! { ::core::marker::PhantomData<fn() -> (#(#unused,)*)> })
+    }
+}
+
+/// Generates `{Name}Patch`, with a function per prop returning a `ConstructPatch` that sets it.
+fn patch_helpers(
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    fields: &ConstructFields,
+    paths: &Paths,
+    construct_where: &TokenStream,
+) -> TokenStream {
+    let cant_wait_for_bsn = &paths.cant_wait_for_bsn;
+    let patch_ident = format_ident!("{}Patch", ident);
+    let props_ident = format_ident!("{}Props", ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let setters = fields.props_fields().filter_map(|field| {
+        let field_ident = field.field.ident.as_ref()?;
+        let props_type = field.props_type(paths)?;
+        let doc = format!(" Patches `{field_ident}` of [`{ident}`].");
+        Some(quote! {
+            #[doc = #doc]
+            #vis fn #field_ident(
+                value: impl ::core::convert::Into<#props_type>,
+            ) -> #cant_wait_for_bsn::ConstructPatch<
+                #ident #ty_generics,
+                impl Fn(&mut #props_ident #ty_generics) + #FQClone + Send + Sync + 'static,
+            >
+            where
+                #props_type: #FQClone + Send + Sync + 'static,
+            {
+                let value = value.into();
+                <#ident #ty_generics as #cant_wait_for_bsn::ConstructPatchExt>::patch(
+                    move |props: &mut #props_ident #ty_generics| {
+                        props.#field_ident = #FQClone::clone(&value);
+                    },
+                )
+                .with_fields(&[::core::stringify!(#field_ident)])
+            }
+        })
+    });
+
+    let doc = format!(
+        " Typed patches for the props of [`{ident}`]. Combine patches of different components in tuples."
+    );
+    quote! {
+        #[doc = #doc]
+        #vis struct #patch_ident #generics (::core::marker::PhantomData<fn() -> #ident #ty_generics>) #where_clause;
+
+        impl #impl_generics #patch_ident #ty_generics #construct_where {
+            #(#setters)*
+        }
+    }
+}
+
+/// Submits a `ConstructRegistration` for the type, so it's registered by `CantWaitForBsnPlugin`.
+///
+/// Generic types can't be registered without knowing their parameters, so they're skipped. They get
+/// `ReflectConstruct` from `#[reflect(Construct)]` instead, which is why the props `Clone` impl is bounded on
+/// the props fields rather than on every type parameter.
+fn registration(ident: &Ident, generics: &Generics, paths: &Paths) -> TokenStream {
+    let Paths {
+        bevy_reflect,
+        cant_wait_for_bsn,
+    } = paths;
+    if !generics.params.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        const _: () = {
+            use #cant_wait_for_bsn::__macro_exports::{RegisterConstruct, RegisterConstructComponent, RegisterNothing};
+
+            fn register(registry: &mut #bevy_reflect::TypeRegistry) {
+                (&&#cant_wait_for_bsn::__macro_exports::RegistrationProbe::<#ident>::default()).register(registry);
+            }
+
+            #cant_wait_for_bsn::__macro_exports::inventory::submit! {
+                #cant_wait_for_bsn::ConstructRegistration::new(register)
+            }
+        };
+    }
+}
+
+/// Collects all identifiers and lifetime names in `tokens`.
+fn collect_idents(tokens: TokenStream, idents: &mut HashSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
             }
-        },
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
     }
 }
+
+fn impl_for_struct(
+    vis: Visibility,
+    ident: Ident,
+    generics: Generics,
+    data_struct: DataStruct,
+    type_attrs: TypeAttrs,
+) -> syn::Result<TokenStream> {
+    let props_ident = Ident::new(format!("{}{}", ident, "Props").as_str(), ident.span());
+
+    let paths = Paths::new();
+    let Paths {
+        bevy_reflect,
+        cant_wait_for_bsn,
+    } = &paths;
+
+    let fields = ConstructFields::new(&data_struct.fields, &paths)?;
+    let props_generics = PropsGenerics::new(&generics, fields.iter(), &paths);
+    let marker = props_generics.marker();
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let props_where = props_generics.where_clause([]);
+    let default_where = props_generics.where_clause(&props_generics.default_predicates);
+    let clone_where = props_generics.where_clause(&props_generics.clone_predicates);
+    let props_bound = parse_quote! { #props_ident #ty_generics: #FQDefault + #FQClone };
+    let construct_where = props_generics.where_clause(
+        props_generics
+            .construct_predicates
+            .iter()
+            .chain([&props_bound]),
+    );
+
+    let declaration = fields.props_declaration(&paths, marker.as_ref());
+    let props_struct = match data_struct.fields {
+        Fields::Named(_) => quote! { #vis struct #props_ident #generics #props_where #declaration },
+        _ => quote! { #vis struct #props_ident #generics #declaration #props_where; },
+    };
+    let default_body = fields.props_default(&paths, marker.is_some());
+    let clone_body = fields.props_clone(marker.is_some());
+    let pattern = fields.props_pattern();
+    let construct_body = fields.construct_body(&paths);
+    let registration = registration(&ident, &generics, &paths);
+    let patch = match type_attrs.patch {
+        Some(patch) => {
+            let Fields::Named(_) = data_struct.fields else {
+                return Err(syn::Error::new(
+                    patch.span(),
+                    "`#[construct(patch)]` is only supported on structs with named fields",
+                ));
+            };
+            patch_helpers(&vis, &ident, &generics, &fields, &paths, &construct_where)
+        }
+        None => quote! {},
+    };
+
+    Ok(quote! {
+        #[allow(missing_docs)]
+        #[derive(#bevy_reflect::Reflect)]
+        #props_struct
+
+        impl #impl_generics #FQDefault for #props_ident #ty_generics #default_where {
+            fn default() -> Self {
+                Self #default_body
+            }
+        }
+
+        impl #impl_generics #FQClone for #props_ident #ty_generics #clone_where {
+            fn clone(&self) -> Self {
+                let Self #pattern = self;
+                Self #clone_body
+            }
+        }
+
+        impl #impl_generics #cant_wait_for_bsn::Construct for #ident #ty_generics #construct_where {
+            type Props = #props_ident #ty_generics;
+            fn construct(context: &mut #cant_wait_for_bsn::ConstructContext, props: Self::Props) -> #FQResult<Self, #cant_wait_for_bsn::ConstructError> {
+                let #props_ident #pattern = props;
+                Ok(Self #construct_body)
+            }
+        }
+
+        #patch
+
+        #registration
+    })
+}
+
+fn impl_for_enum(
+    vis: Visibility,
+    ident: Ident,
+    generics: Generics,
+    data_enum: DataEnum,
+) -> syn::Result<TokenStream> {
+    let props_ident = Ident::new(format!("{}{}", ident, "Props").as_str(), ident.span());
+
+    let paths = Paths::new();
+    let Paths {
+        bevy_reflect,
+        cant_wait_for_bsn,
+    } = &paths;
+
+    let mut default_variants = data_enum.variants.iter().filter(|variant| {
+        variant
+            .attrs
+            .iter()
+            .any(|attr| attr.meta.path().is_ident("default"))
+    });
+    let Some(default_variant) = default_variants.next() else {
+        return Err(syn::Error::new(
+            ident.span(),
+            "`#[derive(Construct)]` on enums requires a `#[default]` variant",
+        ));
+    };
+    if let Some(variant) = default_variants.next() {
+        return Err(syn::Error::new(
+            variant.ident.span(),
+            "multiple `#[default]` variants",
+        ));
+    }
+
+    let variants = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            Ok((
+                &variant.ident,
+                ConstructFields::new(&variant.fields, &paths)?,
+            ))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let props_generics = PropsGenerics::new(
+        &generics,
+        variants.iter().flat_map(|(_, fields)| fields.iter()),
+        &paths,
+    );
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let props_where = props_generics.where_clause([]);
+    let default_where = props_generics.where_clause(&props_generics.default_predicates);
+    let clone_where = props_generics.where_clause(&props_generics.clone_predicates);
+    let props_bound = parse_quote! { #props_ident #ty_generics: #FQDefault + #FQClone };
+    let construct_where = props_generics.where_clause(
+        props_generics
+            .construct_predicates
+            .iter()
+            .chain([&props_bound]),
+    );
+    let registration = registration(&ident, &generics, &paths);
+
+    // Parameters no props field uses are carried by a marker field on the default variant.
+    let default_variant_ident = &default_variant.ident;
+    let marker = props_generics.marker();
+    let props_variants = variants.iter().map(|(variant_ident, fields)| {
+        let marker = marker
+            .as_ref()
+            .filter(|_| *variant_ident == default_variant_ident);
+        let declaration = fields.props_declaration(&paths, marker);
+        quote! { #variant_ident #declaration }
+    });
+
+    let default_body = variants
+        .iter()
+        .find(|(variant_ident, _)| *variant_ident == default_variant_ident)
+        .map(|(_, fields)| fields.props_default(&paths, marker.is_some()));
+
+    let clone_arms = variants
+        .iter()
+        .map(|(variant_ident, fields)| {
+            let pattern = fields.props_pattern();
+            let body =
+                fields.props_clone(marker.is_some() && *variant_ident == default_variant_ident);
+            quote! { Self::#variant_ident #pattern => Self::#variant_ident #body }
+        })
+        .collect::<Vec<_>>();
+
+    let construct_arms = variants
+        .iter()
+        .map(|(variant_ident, fields)| {
+            let pattern = fields.props_pattern();
+            let body = fields.construct_body(&paths);
+            quote! { #props_ident::#variant_ident #pattern => Self::#variant_ident #body }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        #[allow(missing_docs)]
+        #[derive(#bevy_reflect::Reflect)]
+        #vis enum #props_ident #generics #props_where {
+            #(#props_variants),*
+        }
+
+        impl #impl_generics #FQDefault for #props_ident #ty_generics #default_where {
+            fn default() -> Self {
+                Self::#default_variant_ident #default_body
+            }
+        }
+
+        impl #impl_generics #FQClone for #props_ident #ty_generics #clone_where {
+            fn clone(&self) -> Self {
+                match self {
+                    #(#clone_arms),*
+                }
+            }
+        }
+
+        impl #impl_generics #cant_wait_for_bsn::Construct for #ident #ty_generics #construct_where {
+            type Props = #props_ident #ty_generics;
+            fn construct(context: &mut #cant_wait_for_bsn::ConstructContext, props: Self::Props) -> #FQResult<Self, #cant_wait_for_bsn::ConstructError> {
+                Ok(match props {
+                    #(#construct_arms),*
+                })
+            }
+        }
+
+        #registration
+    })
+}
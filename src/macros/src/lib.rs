@@ -13,7 +13,26 @@ pub fn bsn_hot(item: TokenStream) -> TokenStream {
     bsn::bsn_hot(item.into()).into()
 }
 
-#[proc_macro_derive(Construct, attributes(construct))]
+/// Derives `Construct`, generating a `{Name}Props` type that mirrors the fields of the type.
+///
+/// Field attributes:
+/// - `#[construct]`: The prop is a `ConstructProp<T>`, constructed from the props of `T` unless given a value.
+/// - `#[construct(default = expr)]`: Default value of the prop.
+/// - `#[construct(skip)]`: The field is not a prop. It's set to `default`, or `Default::default()`, at construct time.
+/// - `#[construct(with = path)]`: Constructs the field with `path(context, prop)`, or `path(context)` if skipped.
+/// - `#[construct(props = Type)]`: The prop is of `Type`, and constructed into the field via `Construct`.
+/// - `#[construct(asset)]`: The `Handle<T>` field is constructed through `ConstructHandle<T>`, so it can be given as an `@"path"` prop.
+/// - `#[construct(entity)]`: The `Entity` field is constructed through `ConstructEntity`, so it can be given as an `@"name"` prop.
+///
+/// With `#[construct(patch)]` on a struct, a `{Name}Patch` type is generated with a function per prop,
+/// returning a `ConstructPatch` that sets it, e.g. `PlayerPatch::name("fred")`.
+///
+/// Enums need a `#[default]` variant, which is the variant of the default props.
+/// Type parameters that no prop uses are carried by a `PhantomData` field of the default props variant.
+///
+/// Non-generic types are registered by `CantWaitForBsnPlugin`, along with their props, `ReflectConstruct` and,
+/// for components, `ReflectComponent`. This requires the type and its props to be reflectable.
+#[proc_macro_derive(Construct, attributes(construct, default))]
 pub fn derive_construct(item: TokenStream) -> TokenStream {
     derive_construct::derive_construct(item.into()).into()
 }
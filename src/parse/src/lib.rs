@@ -1,11 +1,11 @@
-use std::hash::Hash;
+use std::{fmt, hash::Hash};
 
 use syn::{
     braced, bracketed, parenthesized,
-    parse::{Parse, ParseStream},
+    parse::{discouraged::Speculative, Parse, ParseStream},
     punctuated::Punctuated,
     token::{self, Brace, Paren},
-    Expr, Member, Path, Result, Token,
+    Error, Expr, Ident, Index, LitFloat, Member, Pat, Path, Result, Token,
 };
 
 pub mod from_bsn;
@@ -17,17 +17,14 @@ pub use syn;
 
 // TODO: Better rust-analyzer support
 
-// TODO: Support nested constructs? E.g:
-// bsn! {
-//    ConstructOuter {
-//        some_prop: ConstructInner {
-//            inner_prop: @"asset.txt",
-//        }
-//    }
-// Might be the reason that braces are required for expressions in the first place.
-
 #[derive(Debug, Hash)]
 pub struct BsnEntity {
+    /// Name of the entity in the `SceneRefs` of the scene instance, given by `#[ref(name)]`.
+    pub ref_name: Option<Ident>,
+    /// Key matching the entity to an existing child when reconstructing, given by `#[key(expr)]`.
+    pub key: Option<Expr>,
+    /// Whether this is a fragment, `<> [A, B]`, which only has children to splice into its parent.
+    pub fragment: bool,
     pub inherits: Punctuated<BsnInherit, Token![,]>,
     pub patch: BsnPatch,
     pub children: Punctuated<BsnChildren, Token![,]>,
@@ -36,8 +33,36 @@ pub struct BsnEntity {
 impl Parse for BsnEntity {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut inherits = Punctuated::new();
+        let mut ref_name = None;
+        let mut key = None;
+        while input.peek(Token![#]) {
+            input.parse::<Token![#]>()?;
+            let content;
+            bracketed![content in input];
+            let args;
+            if content.peek(Token![ref]) {
+                content.parse::<Token![ref]>()?;
+                parenthesized![args in content];
+                ref_name = Some(args.parse::<Ident>()?);
+            } else {
+                let name = content.parse::<Ident>()?;
+                if name != "key" {
+                    return Err(Error::new(name.span(), "expected `ref` or `key`"));
+                }
+                parenthesized![args in content];
+                key = Some(args.parse::<Expr>()?);
+            }
+        }
+        let fragment = input.peek(Token![<]) && input.peek2(Token![>]);
+        if fragment && (ref_name.is_some() || key.is_some()) {
+            return Err(input.error("fragments can't have `#[ref(..)]` or `#[key(..)]`"));
+        }
         let patch;
-        if input.peek(Paren) {
+        if fragment {
+            input.parse::<Token![<]>()?;
+            input.parse::<Token![>]>()?;
+            patch = BsnPatch::Tuple(Punctuated::new());
+        } else if input.peek(Paren) {
             let content;
             parenthesized![content in input];
 
@@ -86,6 +111,9 @@ impl Parse for BsnEntity {
         };
 
         Ok(Self {
+            ref_name,
+            key,
+            fragment,
             inherits,
             patch,
             children,
@@ -97,11 +125,15 @@ impl Parse for BsnEntity {
 pub enum BsnChildren {
     Entity(BsnEntity),
     Spread(Expr),
+    /// A child that is only spawned if the condition holds, or one of several children.
+    Conditional(BsnConditional<BsnEntity>),
 }
 
 impl Parse for BsnChildren {
     fn parse(input: ParseStream) -> Result<Self> {
-        if input.peek(Token![.]) && input.peek2(Token![.]) {
+        if BsnConditional::<BsnEntity>::peek(input) {
+            Ok(BsnChildren::Conditional(input.parse()?))
+        } else if input.peek(Token![.]) && input.peek2(Token![.]) {
             // Parse as spread
             input.parse::<Token![.]>()?;
             input.parse::<Token![.]>()?;
@@ -114,15 +146,22 @@ impl Parse for BsnChildren {
 
 #[derive(Debug, Hash)]
 pub enum BsnPatch {
-    Patch(Path, Vec<(Member, BsnProp)>),
+    /// Merges the fields into the props: `Path { field: value }`.
+    Patch(Path, Vec<(BsnMemberPath, BsnProp)>),
+    /// Resets the props to their default before setting the fields: `=Path { field: value }`.
+    Replace(Path, Vec<(BsnMemberPath, BsnProp)>),
     Tuple(Punctuated<BsnPatch, Token![,]>),
     Expr(Expr),
+    /// A patch that is only applied if the condition holds, or one of several patches.
+    Conditional(BsnConditional<BsnPatch>),
 }
 
 impl Parse for BsnPatch {
     fn parse(input: ParseStream) -> Result<BsnPatch> {
         // TODO: Flatten tuples recursively?
-        if input.peek(Paren) {
+        if BsnConditional::<BsnPatch>::peek(input) {
+            Ok(BsnPatch::Conditional(input.parse()?))
+        } else if input.peek(Paren) {
             // Tuple
             let content;
             parenthesized![content in input];
@@ -134,65 +173,136 @@ impl Parse for BsnPatch {
             braced![content in input];
             let expr = content.parse::<Expr>()?;
             Ok(BsnPatch::Expr(expr))
+        } else if input.peek(Token![=]) {
+            // Replace
+            input.parse::<Token![=]>()?;
+            let (path, fields) = parse_patch_fields(input)?;
+            Ok(BsnPatch::Replace(path, fields))
         } else {
-            // TODO: Maybe also support fallback-to-expression for maybe-structs that don't turn out to be parsable as struct
-            // Another idea is to treat paths where last segment is lowercase (probably function call) as expr by default. (bit weird, but should be good a nice DX)
-            let path = input.parse::<Path>()?;
+            let (path, fields) = parse_patch_fields(input)?;
+            Ok(BsnPatch::Patch(path, fields))
+        }
+    }
+}
 
-            let fields = if input.peek(Paren) {
-                // Tuple struct
-                let content;
-                parenthesized![content in input];
-                content
-                    .parse_terminated(BsnProp::parse, Token![,])?
-                    .iter()
-                    .enumerate()
-                    .map(|(i, prop)| (Member::from(i), prop.clone())) // TODO: Avoid clone?
-                    .collect()
-            } else if input.peek(Brace) {
-                // Struct (braced)
+fn parse_patch_fields(input: ParseStream) -> Result<(Path, Vec<(BsnMemberPath, BsnProp)>)> {
+    // TODO: Maybe also support fallback-to-expression for maybe-structs that don't turn out to be parsable as struct
+    // Another idea is to treat paths where last segment is lowercase (probably function call) as expr by default. (bit weird, but should be good a nice DX)
+    let path = input.parse::<Path>()?;
+
+    let fields = if input.peek(Paren) {
+        // Tuple struct
+        let content;
+        parenthesized![content in input];
+        content
+            .parse_terminated(BsnProp::parse, Token![,])?
+            .iter()
+            .enumerate()
+            .map(|(i, prop)| (Member::from(i).into(), prop.clone())) // TODO: Avoid clone?
+            .collect()
+    } else if input.peek(Brace) {
+        // Struct (braced)
+        let content;
+        braced![content in input];
+        parse_named_props(&content)?
+    } else {
+        Vec::new()
+    };
+
+    Ok((path, fields))
+}
+
+/// Conditional entries: `if a { .. } else if b { .. } else { .. }` or `match x { pattern => .., }`.
+#[derive(Debug, Hash)]
+pub enum BsnConditional<T> {
+    /// Branches of an `if` chain. The condition is `None` for the final `else`.
+    If(Vec<(Option<Expr>, T)>),
+    /// The scrutinee and arms of a `match`. The scrutinee is boxed to keep the enum small.
+    Match(Box<Expr>, Vec<BsnArm<T>>),
+}
+
+impl<T> BsnConditional<T> {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Token![if]) || input.peek(Token![match])
+    }
+
+    /// Returns the body of the branch at `index`, or `None` if there is no such branch.
+    pub fn branch(&self, index: usize) -> Option<&T> {
+        match self {
+            BsnConditional::If(branches) => branches.get(index).map(|(_, body)| body),
+            BsnConditional::Match(_, arms) => arms.get(index).map(|arm| &arm.body),
+        }
+    }
+}
+
+impl<T: Parse> Parse for BsnConditional<T> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![match]) {
+            input.parse::<Token![match]>()?;
+            let expr = Expr::parse_without_eager_brace(input)?;
+            let content;
+            braced![content in input];
+            let mut arms = Vec::new();
+            while !content.is_empty() {
+                let pat = Pat::parse_multi_with_leading_vert(&content)?;
+                let guard = match content.parse::<Option<Token![if]>>()? {
+                    Some(_) => Some(content.parse::<Expr>()?),
+                    None => None,
+                };
+                content.parse::<Token![=>]>()?;
+                let body = content.parse::<T>()?;
+                content.parse::<Option<Token![,]>>()?;
+                arms.push(BsnArm { pat, guard, body });
+            }
+            return Ok(BsnConditional::Match(Box::new(expr), arms));
+        }
+
+        let mut branches = Vec::new();
+        loop {
+            input.parse::<Token![if]>()?;
+            let condition = Expr::parse_without_eager_brace(input)?;
+            let content;
+            braced![content in input];
+            branches.push((Some(condition), content.parse::<T>()?));
+
+            if input.parse::<Option<Token![else]>>()?.is_none() {
+                break;
+            }
+            if !input.peek(Token![if]) {
                 let content;
                 braced![content in input];
-                content
-                    .parse_terminated(
-                        |input| {
-                            let member: Member = input.parse()?;
-                            let _colon_token: Token![:] = input.parse()?;
-                            let prop: BsnProp = input.parse()?;
-                            Ok((member, prop))
-                        },
-                        Token![,],
-                    )?
-                    .iter()
-                    .cloned() // TODO: Avoid clone?
-                    .collect()
-            } else {
-                Vec::new()
-            };
-
-            Ok(BsnPatch::Patch(path, fields))
+                branches.push((None, content.parse::<T>()?));
+                break;
+            }
         }
+        Ok(BsnConditional::If(branches))
     }
 }
 
+/// An arm of a `match` entry.
+#[derive(Debug, Hash)]
+pub struct BsnArm<T> {
+    pub pat: Pat,
+    pub guard: Option<Expr>,
+    pub body: T,
+}
+
 #[derive(Debug, Hash, Clone)]
 pub enum BsnProp {
     Value(Expr),
     Prop(Expr),
+    /// Props of a nested construct, patched field by field: `@Inner { field: @"asset.txt" }`.
+    Props(BsnProps),
+    /// Items appended to a list field: `field += [a, b]`.
+    Append(Expr),
 }
 
-impl Into<Expr> for BsnProp {
-    fn into(self) -> Expr {
-        match self {
-            BsnProp::Value(expr) | BsnProp::Prop(expr) => expr,
-        }
-    }
-}
-
-impl<'a> Into<&'a Expr> for &'a BsnProp {
-    fn into(self) -> &'a Expr {
+impl BsnProp {
+    /// Returns the expression of the prop, or `None` for nested props.
+    pub fn expr(&self) -> Option<&Expr> {
         match self {
-            BsnProp::Value(expr) | BsnProp::Prop(expr) => expr,
+            BsnProp::Value(expr) | BsnProp::Prop(expr) | BsnProp::Append(expr) => Some(expr),
+            BsnProp::Props(_) => None,
         }
     }
 }
@@ -200,6 +310,16 @@ impl<'a> Into<&'a Expr> for &'a BsnProp {
 impl Parse for BsnProp {
     fn parse(input: ParseStream) -> Result<BsnProp> {
         let is_prop = input.parse::<Token![@]>().is_ok();
+        if is_prop {
+            // Braces with anything but `field: value` entries, like `..base`, are a struct expression instead
+            let fork = input.fork();
+            if let Ok(props) = fork.parse::<BsnProps>() {
+                if fork.is_empty() || fork.peek(Token![,]) {
+                    input.advance_to(&fork);
+                    return Ok(BsnProp::Props(props));
+                }
+            }
+        }
         let expr = input.parse::<Expr>()?;
         match is_prop {
             true => Ok(BsnProp::Prop(expr)),
@@ -208,6 +328,106 @@ impl Parse for BsnProp {
     }
 }
 
+/// Nested props: the path of the construct type, followed by the patched fields of its props.
+#[derive(Debug, Hash, Clone)]
+pub struct BsnProps {
+    pub path: Path,
+    pub brace_token: Brace,
+    pub fields: Vec<(BsnMemberPath, BsnProp)>,
+}
+
+impl Parse for BsnProps {
+    fn parse(input: ParseStream) -> Result<BsnProps> {
+        let path = input.parse::<Path>()?;
+        let content;
+        let brace_token = braced![content in input];
+        let fields = parse_named_props(&content)?;
+        Ok(BsnProps {
+            path,
+            brace_token,
+            fields,
+        })
+    }
+}
+
+fn parse_named_props(input: ParseStream) -> Result<Vec<(BsnMemberPath, BsnProp)>> {
+    Ok(input
+        .parse_terminated(
+            |input| {
+                let member: BsnMemberPath = input.parse()?;
+                if input.parse::<Option<Token![+=]>>()?.is_some() {
+                    return Ok((member, BsnProp::Append(input.parse()?)));
+                }
+                let _colon_token: Token![:] = input.parse()?;
+                let prop: BsnProp = input.parse()?;
+                Ok((member, prop))
+            },
+            Token![,],
+        )?
+        .into_iter()
+        .collect())
+}
+
+/// The patched member of a field, or a dotted path into nested members: `translation.x`.
+#[derive(Debug, Hash, Clone, PartialEq)]
+pub struct BsnMemberPath(pub Vec<Member>);
+
+impl BsnMemberPath {
+    /// Returns the member if the path has a single member.
+    pub fn as_member(&self) -> Option<&Member> {
+        match self.0.as_slice() {
+            [member] => Some(member),
+            _ => None,
+        }
+    }
+}
+
+impl From<Member> for BsnMemberPath {
+    fn from(member: Member) -> Self {
+        Self(vec![member])
+    }
+}
+
+impl fmt::Display for BsnMemberPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, member) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            match member {
+                Member::Named(name) => write!(f, "{name}")?,
+                Member::Unnamed(index) => write!(f, "{}", index.index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Parse for BsnMemberPath {
+    fn parse(input: ParseStream) -> Result<BsnMemberPath> {
+        let mut members = vec![input.parse::<Member>()?];
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            if input.peek(LitFloat) {
+                // `a.0.1` is lexed as `a`, `.`, `0.1`
+                let lit = input.parse::<LitFloat>()?;
+                for index in lit.base10_digits().split('.') {
+                    let index = index
+                        .parse()
+                        .map_err(|_| Error::new(lit.span(), "expected tuple index"))?;
+                    members.push(Member::Unnamed(Index {
+                        index,
+                        span: lit.span(),
+                    }));
+                }
+            } else {
+                members.push(input.parse()?);
+            }
+        }
+        Ok(BsnMemberPath(members))
+    }
+}
+
 #[derive(Debug, Hash, Clone)]
 pub struct BsnInherit(pub Path, pub Punctuated<Expr, Token![,]>);
 
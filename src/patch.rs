@@ -41,9 +41,36 @@ all_tuples!(
 /// Generic patch based on closure
 pub struct ConstructPatch<C: Construct, F> {
     pub(crate) func: F,
+    pub(crate) fields: Option<&'static [&'static str]>,
+    pub(crate) replace: bool,
+    pub(crate) reset_appended: Option<fn(&mut C::Props)>,
     pub(crate) _marker: PhantomData<C>,
 }
 
+impl<C: Construct, F> ConstructPatch<C, F> {
+    /// Declares the fields of the props set by this patch. Without it, the patch is assumed to replace the props as a whole.
+    ///
+    /// Only used for provenance tracking.
+    pub fn with_fields(mut self, fields: &'static [&'static str]) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    /// Resets the props to their default before applying this patch, instead of merging into them.
+    pub fn replacing(mut self) -> Self {
+        self.replace = true;
+        self
+    }
+
+    /// Declares how to reset the fields this patch appends to back to their default.
+    ///
+    /// Hot patching resets them before applying the patch, so reconstructing a scene doesn't append the items again.
+    pub fn appending(mut self, reset: fn(&mut C::Props)) -> Self {
+        self.reset_appended = Some(reset);
+        self
+    }
+}
+
 impl<C, F> ConstructPatch<C, F>
 where
     C: Construct<Props = C>,
@@ -55,6 +82,9 @@ where
     pub fn new_inferred(func: F) -> Self {
         Self {
             func,
+            fields: None,
+            replace: false,
+            reset_appended: None,
             _marker: PhantomData,
         }
     }
@@ -65,6 +95,9 @@ impl<C: Construct + Bundle, F: Fn(&mut C::Props) + Clone + Sync + Send + 'static
 {
     type Construct = C;
     fn patch(&mut self, props: &mut <Self::Construct as Construct>::Props) {
+        if self.replace {
+            *props = Default::default();
+        }
         (self.func)(props);
     }
 }
@@ -86,6 +119,9 @@ pub trait ConstructPatchExt {
     ) -> ConstructPatch<Self::C, F> {
         ConstructPatch {
             func,
+            fields: None,
+            replace: false,
+            reset_appended: None,
             _marker: PhantomData,
         }
     }
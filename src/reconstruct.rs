@@ -0,0 +1,314 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use bevy::prelude::{
+    BuildChildren, Component, DespawnRecursiveExt, Entity, ReflectComponent, Resource, World,
+};
+use bevy::reflect::Reflect;
+
+use crate::{
+    scene_children, ConstructContext, ConstructError, ConstructPatch, DynamicPatch, DynamicScene,
+    ReflectConstruct, Scene,
+};
+
+/// Matches a child scene to an existing child entity when reconstructing, instead of its position.
+/// Inserted by `#[key(expr)]` in `bsn!`.
+#[derive(Component, Reflect, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[reflect(Component, Construct)]
+pub struct SceneKey(pub Cow<'static, str>);
+
+impl SceneKey {
+    /// Creates a key from the displayed value.
+    pub fn new(key: impl fmt::Display) -> Self {
+        Self(key.to_string().into())
+    }
+}
+
+/// The children spawned while reconstructing through [`ConstructContextSceneExt::reconstruct_scene`](crate::ConstructContextSceneExt::reconstruct_scene),
+/// each with the parent it was spawned under. One list per reconstruction in progress, innermost last.
+#[derive(Resource, Default)]
+pub(crate) struct ReconstructSpawned(pub(crate) Vec<Vec<(Entity, Entity)>>);
+
+/// Records children spawned under `parent` while reconstructing, to add them to its [`SceneInstance`](crate::SceneInstance).
+pub(crate) fn record_spawned_children(
+    world: &mut World,
+    parent: Entity,
+    children: impl IntoIterator<Item = Entity>,
+) {
+    let Some(mut spawned) = world.get_resource_mut::<ReconstructSpawned>() else {
+        return;
+    };
+    if let Some(spawned) = spawned.0.last_mut() {
+        spawned.extend(children.into_iter().map(|child| (parent, child)));
+    }
+}
+
+/// The existing children of an entity being reconstructed, matched to its child scenes.
+///
+/// Only the children spawned by an earlier construction of the scene are reused, as recorded in the
+/// [`SceneInstance`](crate::SceneInstance) the entity belongs to. Other children are left alone.
+/// Keyed scenes reuse the child with the same [`SceneKey`], and the others reuse the next child without a key.
+pub struct ReconcileChildren {
+    existing: Vec<Entity>,
+    reconciled: Vec<Entity>,
+    spawned: Vec<Entity>,
+}
+
+impl ReconcileChildren {
+    /// Collects the children of the entity spawned by the scene instance being reconstructed.
+    pub fn new(world: &World, parent: Entity) -> Self {
+        let reconstructing = world
+            .get_resource::<ReconstructSpawned>()
+            .is_some_and(|spawned| !spawned.0.is_empty());
+        Self {
+            existing: if reconstructing {
+                scene_children(world, parent)
+            } else {
+                Vec::new()
+            },
+            reconciled: Vec::new(),
+            spawned: Vec::new(),
+        }
+    }
+
+    /// Reconstructs the scene on the matching existing child, or spawns it as a new child of the context entity.
+    pub fn reconstruct_child(
+        &mut self,
+        scene: impl Scene,
+        context: &mut ConstructContext,
+    ) -> Result<(), ConstructError> {
+        let key = scene.key();
+        let index = self
+            .existing
+            .iter()
+            .position(|child| context.world.get::<SceneKey>(*child) == key);
+
+        match index {
+            Some(index) => {
+                let id = self.existing.remove(index);
+                self.reconciled.push(id);
+                scene.reconstruct(&mut ConstructContext {
+                    id,
+                    world: context.world,
+                })
+            }
+            None => {
+                let id = context.world.spawn_empty().id();
+                context.world.entity_mut(context.id).add_child(id);
+                self.reconciled.push(id);
+                self.spawned.push(id);
+                scene.construct(&mut ConstructContext {
+                    id,
+                    world: context.world,
+                })
+            }
+        }
+    }
+
+    /// Despawns the children of the scene that weren't reused, and orders the rest like the child scenes.
+    pub fn finish(self, world: &mut World, parent: Entity) {
+        for child in self.existing {
+            world.entity_mut(child).despawn_recursive();
+        }
+        world.entity_mut(parent).add_children(&self.reconciled);
+        record_spawned_children(world, parent, self.spawned);
+    }
+}
+
+/// A [`Scene`] with a [`SceneKey`], from `#[key(expr)]` in `bsn!`.
+pub struct Keyed<S: Scene> {
+    /// Key of the scene
+    pub key: SceneKey,
+    /// The keyed scene
+    pub scene: S,
+}
+
+impl<S: Scene> Scene for Keyed<S> {
+    fn construct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        context.world.entity_mut(context.id).insert(self.key);
+        self.scene.construct(context)
+    }
+
+    fn spawn(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        let id = context.world.spawn_empty().id();
+        context.world.entity_mut(context.id).add_child(id);
+
+        self.construct(&mut ConstructContext {
+            id,
+            world: context.world,
+        })
+    }
+
+    fn dynamic_patch(&mut self, scene: &mut DynamicScene) {
+        self.scene.dynamic_patch(scene);
+        let key = self.key.clone();
+        ConstructPatch::new_inferred(move |props: &mut SceneKey| {
+            *props = key.clone();
+        })
+        .dynamic_patch(scene);
+    }
+
+    fn dynamic_patch_as_child(&mut self, parent_scene: &mut DynamicScene) {
+        let mut child_scene = parent_scene.new_child();
+        self.dynamic_patch(&mut child_scene);
+        parent_scene.push_child(child_scene);
+    }
+
+    fn reconstruct(self, context: &mut ConstructContext) -> Result<(), ConstructError> {
+        context.world.entity_mut(context.id).insert(self.key);
+        self.scene.reconstruct(context)
+    }
+
+    fn key(&self) -> Option<&SceneKey> {
+        Some(&self.key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{Children, Reflect};
+
+    use super::*;
+    use crate::{
+        bsn, ConstructPatchExt, EntityWorldMutSceneExt, SceneInstance, SceneNodeId, WorldSceneExt,
+    };
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    struct Label(u32);
+
+    fn labels(world: &World, parent: Entity) -> Vec<u32> {
+        world
+            .get::<Children>(parent)
+            .map(|children| {
+                children
+                    .iter()
+                    .map(|child| world.get::<Label>(*child).unwrap().0)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_reconstruct_scene() {
+        let mut world = World::new();
+        let id = world
+            .spawn_scene(bsn! { Label(0u32) [ Label(1u32), Label(2u32) ] })
+            .unwrap();
+        let children = world.get::<Children>(id).unwrap().to_vec();
+
+        // Positional children are reused instead of appended
+        world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! { Label(10u32) [ Label(11u32), Label(12u32) ] })
+            .unwrap();
+        assert_eq!(world.get::<Label>(id), Some(&Label(10)));
+        assert_eq!(world.get::<Children>(id).unwrap().to_vec(), children);
+        assert_eq!(labels(&world, id), vec![11, 12]);
+
+        // Leftovers are despawned
+        world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! { Label(10u32) [ Label(11u32) ] })
+            .unwrap();
+        assert_eq!(world.get::<Children>(id).unwrap().to_vec(), children[..1]);
+        assert!(world.get_entity(children[1]).is_err());
+    }
+
+    #[test]
+    fn test_reconstruct_keeps_unrelated_children() {
+        let mut world = World::new();
+        let id = world
+            .spawn_scene(bsn! { Label(0u32) [ Label(1u32) ] })
+            .unwrap();
+        let child = world.get::<Children>(id).unwrap()[0];
+        let unrelated = world.spawn(Label(9)).id();
+        world.entity_mut(id).add_child(unrelated);
+
+        // Only the children spawned by the scene are reconciled
+        world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! { Label(5u32) })
+            .unwrap();
+        assert_eq!(world.get::<Label>(id), Some(&Label(5)));
+        assert_eq!(world.get::<Children>(id).unwrap().to_vec(), vec![unrelated]);
+        assert!(world.get_entity(child).is_err());
+
+        world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! { Label(5u32) [ Label(6u32) ] })
+            .unwrap();
+        assert_eq!(labels(&world, id), vec![9, 6]);
+
+        // Nor children added under the nodes of the scene
+        let node = world.get::<Children>(id).unwrap()[1];
+        let grandchild = world.spawn(Label(7)).id();
+        world.entity_mut(node).add_child(grandchild);
+        world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! { Label(5u32) [ Label(6u32) [ Label(3u32) ] ] })
+            .unwrap();
+        assert_eq!(labels(&world, node), vec![7, 3]);
+        let instance = world.get::<SceneInstance>(id).unwrap();
+        assert_eq!(instance.node(grandchild), None);
+
+        world
+            .entity_mut(id)
+            .reconstruct_scene(bsn! { Label(5u32) [ Label(6u32) ] })
+            .unwrap();
+        assert_eq!(
+            world.get::<Children>(node).unwrap().to_vec(),
+            vec![grandchild]
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_nested_node() {
+        let mut world = World::new();
+        let id = world
+            .spawn_scene(bsn! { Label(0u32) [ Label(1u32) [ Label(2u32) ] ] })
+            .unwrap();
+        let node = world.get::<Children>(id).unwrap()[0];
+        let leaf = world.get::<Children>(node).unwrap()[0];
+
+        // The children the instance spawned for the node are reused, and new ones join the instance
+        for _ in 0..2 {
+            world
+                .entity_mut(node)
+                .reconstruct_scene(bsn! { Label(8u32) [ Label(3u32), Label(4u32) ] })
+                .unwrap();
+            assert_eq!(world.get::<Label>(node), Some(&Label(8)));
+            assert_eq!(labels(&world, node), vec![3, 4]);
+        }
+        let children = world.get::<Children>(node).unwrap().to_vec();
+        assert_eq!(children[0], leaf);
+        let instance = world.get::<SceneInstance>(id).unwrap();
+        assert_eq!(instance.entity(&SceneNodeId(vec![0, 1])), Some(children[1]));
+        assert!(world.get::<SceneInstance>(node).is_none());
+    }
+
+    #[test]
+    fn test_reconstruct_keyed_scene() {
+        let mut world = World::new();
+        let id = world
+            .spawn_scene(bsn! { Label(0u32) [ #[key("a")] Label(1u32), #[key(2)] Label(2u32) ] })
+            .unwrap();
+        let children = world.get::<Children>(id).unwrap().to_vec();
+
+        // Keyed children follow their key when reordered
+        world
+            .entity_mut(id)
+            .reconstruct_scene(
+                bsn! { Label(0u32) [ #[key(2)] Label(2u32), #[key("a")] Label(1u32) ] },
+            )
+            .unwrap();
+        assert_eq!(
+            world.get::<Children>(id).unwrap().to_vec(),
+            vec![children[1], children[0]]
+        );
+        assert_eq!(labels(&world, id), vec![2, 1]);
+        assert_eq!(
+            world.get::<SceneKey>(children[0]),
+            Some(&SceneKey::new("a"))
+        );
+    }
+}